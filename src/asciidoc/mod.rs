@@ -0,0 +1,390 @@
+use std::cmp;
+
+use junit2md::lang_specific::*;
+use junit2md::md::*;
+use junit2md::model::*;
+
+use crate::{failure_anchor, format_suite_start};
+use crate::options::*;
+use crate::render::*;
+use crate::totals::*;
+
+/// Cells of totals tables, see `TotalsCells`
+const TOTALS_CELLS: TotalsCells = TotalsCells {
+    escape: escape_cell,
+    marker: "⚠",
+    emphasize: |label| format!("*{}*", label),
+    link: |text, anchor| format!("<<{},{}>>", anchor, text),
+};
+
+/// Formatting of testcase breakdown table cells in AsciiDoc
+const TESTCASE_CELLS: TestcaseCells = TestcaseCells {
+    escape: escape_cell,
+    failure_ref: |fail_index, anchor| match anchor {
+        Some(anchor) => format!("<<{},[{}]>>", anchor, fail_index),
+        None => format!("[{}]", fail_index),
+    },
+    anchor: |anchor| format!("[[{}]]", anchor),
+};
+
+/// Characters that may start AsciiDoc inline formatting, macros or replacements
+const SPECIAL_CHARS: &[char] = &['*', '_', '`', '#', '^', '~', '[', ']', '{', '}', '+', '\\', '<', '>', '|', '&'];
+
+/// Renders the report as AsciiDoc, with the same sections as the Markdown report
+pub struct AsciidocRenderer<'a> {
+    adoc: String,
+    opts: &'a ReportOptions,
+}
+
+impl<'a> AsciidocRenderer<'a> {
+    pub fn new(opts: &'a ReportOptions) -> Self {
+        return AsciidocRenderer { adoc: String::new(), opts };
+    }
+}
+
+impl ReportRenderer for AsciidocRenderer<'_> {
+    fn header(&mut self, title: &str) {
+        self.adoc.push_str(&format!("= {}\n\n", escape_text(title)));
+    }
+
+    fn health_badge(&mut self, badge: &str) {
+        self.adoc.push_str(&format!("{}\n\n", badge));
+    }
+
+    fn summary_line(&mut self, summary: &str) {
+        self.adoc.push_str(&format!("{}\n\n", summary));
+    }
+
+    fn suite_status(&mut self, status: &str) {
+        self.adoc.push_str(&format!("{}\n\n", escape_text(status)));
+    }
+
+    fn no_suites(&mut self) {
+        self.adoc.push_str("No test suites found\n\n");
+    }
+
+    fn suite_properties(&mut self, suite: &TestSuite) {
+        add_suite_properties(&mut self.adoc, suite, self.opts);
+    }
+
+    fn report_properties(&mut self, label: &str, properties: &[&TestProperty]) {
+        add_properties_list(&mut self.adoc, label, properties);
+    }
+
+    fn table_of_contents(&mut self, entries: &[TocEntry]) {
+        for entry in entries {
+            let name = escape_text(entry.suite.short_name(&self.opts.lang));
+            let link = match entry.first_failure {
+                Some(fail_index) => format!("<<{},{}>>", failure_anchor(fail_index), name),
+                None => name,
+            };
+            match entry.failed {
+                0 => self.adoc.push_str(&format!("* {} (no failures)\n", link)),
+                _ => self.adoc.push_str(&format!("* {} ({})\n", link, pluralize(entry.failed, "failure"))),
+            }
+        }
+        self.adoc.push('\n');
+    }
+
+    fn section(&mut self, title: &str) {
+        self.adoc.push_str(&format!("== {}\n\n", title));
+    }
+
+    fn suite_totals(&mut self, suite: &TestSuite) {
+        add_totals_singular(&mut self.adoc, suite, self.opts);
+    }
+
+    fn totals(&mut self, suites: &[TestSuite], totals: &ReportTotals) {
+        add_totals_multiple(&mut self.adoc, suites, totals, self.opts);
+    }
+
+    fn baseline_suites(&mut self, added: &[&str], removed: &[&str]) {
+        let escape = |names: &[&str]| names.iter().map(|name| escape_text(name)).collect::<Vec<String>>().join(", ");
+        if !added.is_empty() {
+            self.adoc.push_str(&format!("New suites since baseline: {}\n\n", escape(added)));
+        }
+        if !removed.is_empty() {
+            self.adoc.push_str(&format!("Removed suites since baseline: {}\n\n", escape(removed)));
+        }
+    }
+
+    fn testcase_summary(&mut self, rows: &[TestcaseRow]) {
+        let table = testcase_rows(rows, self.opts, &TESTCASE_CELLS, |name| escape_cell(strip_namespace(name, &self.opts.lang)));
+        create_table(&mut self.adoc, table, true);
+    }
+
+    fn durations(&mut self, sparkline: &str) {
+        self.adoc.push_str(&format!("Test durations: {}\n\n", sparkline));
+    }
+
+    fn test_output(&mut self, test: &TestCase) {
+        self.adoc.push_str(&format!("=== {}\n\n", escape_text(&test.name)));
+
+        if let Some(out) = &test.system_out {
+            add_code_section(&mut self.adoc, self.opts.labels.stdout(), "Test stdout", out, self.opts);
+        }
+
+        if let Some(err) = &test.system_err {
+            add_code_section(&mut self.adoc, self.opts.labels.stderr(), "Test stderr", err, self.opts);
+        }
+    }
+
+    fn failure_detail(&mut self, failure: &FailureDetail) {
+        add_failure_detail(&mut self.adoc, failure, self.opts);
+    }
+
+    fn flaky_test(&mut self, test: &TestCase) {
+        self.adoc.push_str(&format!("=== {}\n\n", escape_text(&test.name)));
+        for (index, rerun) in test.rerun_results().enumerate() {
+            let message = rerun.message.as_deref().unwrap_or("Not specified");
+            self.adoc.push_str(&format!("Failed run {}: `{}`\n\n", index + 1, escape_text(message)));
+            if let Some(stack_trace) = &rerun.stack_trace {
+                add_code_section(&mut self.adoc, self.opts.labels.details(), "Details", stack_trace, self.opts);
+            }
+        }
+    }
+
+    fn omitted_failures(&mut self, count: u64) {
+        self.adoc.push_str(&format!("... and {} (not shown)\n\n", pluralize(count, "more failure")));
+    }
+
+    fn class_summary(&mut self, classes: &[ClassCounts]) {
+        let rows = class_counts_rows(classes, |name| escape_cell(strip_namespace(name, &self.opts.lang)));
+        create_table(&mut self.adoc, rows, true);
+    }
+
+    fn skipped_list(&mut self, skipped: &[SkippedTest]) {
+        let mut table = vec![vec!["Testcase name".to_owned(), "Reason".to_owned()]];
+        for SkippedTest { test, reason } in skipped {
+            table.push(vec![escape_cell(strip_namespace(&test.name, &self.opts.lang)), escape_cell(reason)]);
+        }
+
+        create_table(&mut self.adoc, table, true);
+    }
+
+    fn input_source(&mut self, path: &str, source: &str) {
+        self.adoc.push_str(&format!(".{}\n[%collapsible]\n====\n", escape_text(path)));
+        add_listing(&mut self.adoc, "[source,xml]\n", source);
+        self.adoc.push_str("====\n\n");
+    }
+
+    fn footer(&mut self, footer: &str) {
+        self.adoc.push_str(&format!("_{}_\n", escape_text(footer)));
+    }
+
+    fn finish(self) -> String {
+        return self.adoc;
+    }
+}
+
+/// Escapes text so AsciiDoc shows it as is. Plain text is left untouched,
+/// text with formatting characters is wrapped into inline passthrough.
+///
+/// Arguments:
+/// * `text` - text to escape.
+fn escape_text(text: &str) -> String {
+    if !text.contains(SPECIAL_CHARS) {
+        return text.to_owned();
+    }
+
+    if !text.contains('+') {
+        return format!("+{}+", text);
+    }
+
+    // plus signs would close the short passthrough, use the macro with special characters escaped
+    return format!("pass:c[{}]", text.replace(']', "\\]"));
+}
+
+/// Escapes text for a table cell. Pipes would start a new cell otherwise.
+fn escape_cell(text: &str) -> String {
+    return escape_text(text).replace('|', "\\|");
+}
+
+/// Adds start time, host and properties of the suite.
+///
+/// Arguments:
+/// * `adoc` - document to append to.
+/// * `suite` - test suite to get properties from.
+/// * `opts` - report options.
+fn add_suite_properties(adoc: &mut String, suite: &TestSuite, opts: &ReportOptions) {
+    if let (Some(timestamp), Some(hostname), Some(time)) = (&suite.timestamp, &suite.hostname, &suite.time) {
+        let timestamp = format_suite_start(timestamp, opts);
+        adoc.push_str(&format!("Testset was started on host {} at {} and took {} seconds to finish.\n\n",
+            escape_text(hostname), escape_text(&timestamp), escape_text(time)));
+    }
+
+    if let Some(properties) = &suite.properties {
+        add_properties_list(adoc, "Properties:", &properties.properties.iter().collect::<Vec<_>>());
+    }
+}
+
+/// Adds labeled list of properties, multi-line values go to listing blocks.
+///
+/// Arguments:
+/// * `adoc` - document to append to.
+/// * `label` - line shown above the list.
+/// * `properties` - properties to list.
+fn add_properties_list(adoc: &mut String, label: &str, properties: &[&TestProperty]) {
+    adoc.push_str(&format!("{}\n\n", label));
+    for prop in properties {
+        let value = prop.value.as_deref().unwrap_or_default();
+        if value.contains('\n') {
+            // multi-line values don't fit into a list item
+            add_listing(adoc, &format!(".{}\n", escape_text(&prop.name)), value);
+            adoc.push('\n');
+            continue;
+        }
+
+        adoc.push_str(&format!("* {}: {}\n", escape_text(&prop.name), escape_text(value)));
+    }
+    adoc.push('\n');
+}
+
+/// Creates table with a header row.
+///
+/// Arguments:
+/// * `adoc` - document to append the table to.
+/// * `rows` - table rows, top-to-down. Cells are added as is, they should be escaped already.
+/// * `align_left_first_column` - whether the first column is aligned to the left, all columns are centered otherwise.
+fn create_table(adoc: &mut String, rows: Vec<Vec<String>>, align_left_first_column: bool) {
+    if rows.len() < 2 {
+        // we need at least one header row and one value row
+        return;
+    }
+
+    let columns: Vec<&str> = (0..rows[0].len())
+        .map(|index| if index == 0 && align_left_first_column { "<" } else { "^" })
+        .collect();
+
+    adoc.push_str(&format!("[%header,cols=\"{}\"]\n", columns.join(",")));
+    adoc.push_str("|===\n");
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|cell| format!("|{}", cell)).collect();
+        adoc.push_str(&cells.join(" "));
+        adoc.push('\n');
+    }
+    adoc.push_str("|===\n\n");
+}
+
+/// Adds totals table of a single suite.
+fn add_totals_singular(adoc: &mut String, suite: &TestSuite, opts: &ReportOptions) {
+    create_table(adoc, suite_totals_rows(suite, opts, &TOTALS_CELLS), false);
+
+    if !count_success(suite).1 {
+        add_inconsistent_totals_note(adoc, &[suite], opts);
+    }
+}
+
+/// Adds totals table of several suites, with a row for each suite and a total row.
+///
+/// Arguments:
+/// * `adoc` - document to append to.
+/// * `suites` - test suites to get info from.
+/// * `totals` - totals of the whole report.
+/// * `opts` - report options.
+fn add_totals_multiple(adoc: &mut String, suites: &[TestSuite], totals: &ReportTotals, opts: &ReportOptions) {
+    create_table(adoc, totals_rows(suites, totals, opts, &TOTALS_CELLS), true);
+
+    let inconsistent: Vec<&TestSuite> = suites.iter().filter(|suite| !count_success(suite).1).collect();
+    if !inconsistent.is_empty() {
+        add_inconsistent_totals_note(adoc, &inconsistent, opts);
+    }
+}
+
+/// Adds details of a failed test, under an anchor the breakdown table links to.
+///
+/// Arguments:
+/// * `adoc` - document to append to.
+/// * `failure` - failed test to report.
+/// * `opts` - report options.
+fn add_failure_detail(adoc: &mut String, failure: &FailureDetail, opts: &ReportOptions) {
+    let FailureDetail { index, suite_name, ref suite_anchor, test, result } = *failure;
+    if let Some(suite_anchor) = suite_anchor {
+        adoc.push_str(&format!("[[{}]]\n", suite_anchor));
+    }
+    adoc.push_str(&format!("[[{}]]\n", failure_anchor(index)));
+    adoc.push_str(&format!("=== {}\n\n", escape_text(&test.name)));
+
+    if let Some(suite_name) = suite_name {
+        adoc.push_str(&format!("* Suite: {}\n", escape_text(suite_name)));
+    }
+
+    if let Some(classname) = &test.classname {
+        adoc.push_str(&format!("* Classname: {}\n", escape_text(strip_namespace(classname, &opts.lang))));
+    }
+
+    match failure.reason() {
+        (label, Some(message)) => adoc.push_str(&format!("* {}: `{}`\n", label, escape_text(message))),
+        (label, None) => adoc.push_str(&format!("* {}\n", label)),
+    }
+    adoc.push('\n');
+
+    if let Some(body) = &result.body {
+        add_code_section(adoc, opts.labels.details(), "Details", body, opts);
+    }
+
+    if opts.verbose {
+        if let Some(out) = &test.system_out {
+            add_code_section(adoc, opts.labels.stdout(), "Test stdout", out, opts);
+        }
+
+        if let Some(err) = &test.system_err {
+            add_code_section(adoc, opts.labels.stderr(), "Test stderr", err, opts);
+        }
+    }
+}
+
+/// Adds long text, such as failure body or test output, as a listing block.
+/// It's wrapped into collapsible block by default or titled with the label if collapsing is turned off.
+/// Titles are escaped, as summaries can be set by the user, see `--label-details`.
+///
+/// Arguments:
+/// * `adoc` - document to append to.
+/// * `summary` - title of the collapsible block.
+/// * `label` - title of the listing block when it's not collapsed.
+/// * `code` - the text to add.
+/// * `opts` - report options.
+fn add_code_section(adoc: &mut String, summary: &str, label: &str, code: &str, opts: &ReportOptions) {
+    let code = prepare_code(code, opts);
+    if !opts.collapse {
+        add_listing(adoc, &format!(".{}\n", escape_text(label)), &code);
+        adoc.push('\n');
+        return;
+    }
+
+    adoc.push_str(&format!(".{}\n[%collapsible]\n====\n", escape_text(summary)));
+    add_listing(adoc, "", &code);
+    adoc.push_str("====\n\n");
+}
+
+/// Adds listing block with the text shown verbatim.
+/// The delimiter is made longer than any line of dashes inside the text, so it can't close the block early.
+///
+/// Arguments:
+/// * `adoc` - document to append to.
+/// * `title` - block title line, may be empty.
+/// * `code` - the text to add.
+fn add_listing(adoc: &mut String, title: &str, code: &str) {
+    let code = normalize_newlines(code);
+    let longest_dashes = code.lines()
+        .filter(|line| !line.is_empty() && line.chars().all(|c| c == '-'))
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let delimiter = "-".repeat(cmp::max(4, longest_dashes + 1));
+
+    adoc.push_str(title);
+    adoc.push_str(&delimiter);
+    adoc.push('\n');
+    adoc.push_str(&code);
+    if !code.ends_with('\n') {
+        adoc.push('\n');
+    }
+    adoc.push_str(&delimiter);
+    adoc.push('\n');
+}
+
+/// Adds note explaining the inconsistent totals marker.
+fn add_inconsistent_totals_note(adoc: &mut String, suites: &[&TestSuite], opts: &ReportOptions) {
+    adoc.push_str(&format!("NOTE: ⚠ {}\n\n", inconsistent_totals_note(suites, opts, &TOTALS_CELLS)));
+}
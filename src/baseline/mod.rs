@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::process;
+
+use junit2md::md::*;
+use junit2md::model::*;
+
+use crate::json::*;
+use crate::totals::*;
+
+/// Summary of the previous run the report is compared to, see `--baseline`
+#[derive(Debug, Default)]
+pub struct Baseline {
+    /// Totals of the whole previous report
+    pub totals: JsonCounts,
+    /// Counts of each suite, by full suite name
+    pub suites: BTreeMap<String, JsonCounts>,
+}
+
+impl Baseline {
+    /// Returns counts of the suite in the baseline, if it was there
+    pub fn suite(&self, suite: &TestSuite) -> Option<&JsonCounts> {
+        return self.suites.get(suite.display_name());
+    }
+
+    /// Returns names of the suites that are not in the baseline
+    pub fn added_suites<'a>(&self, suites: &'a [TestSuite]) -> Vec<&'a str> {
+        return suites.iter()
+            .map(TestSuite::display_name)
+            .filter(|name| !self.suites.contains_key(*name))
+            .collect();
+    }
+
+    /// Returns names of the baseline suites that are not in the report anymore
+    pub fn removed_suites(&self, suites: &[TestSuite]) -> Vec<&str> {
+        return self.suites.keys()
+            .map(String::as_str)
+            .filter(|name| !suites.iter().any(|suite| suite.display_name() == *name))
+            .collect();
+    }
+}
+
+/// Reads summary of the previous run written with `--format json`.
+/// Unreadable or malformed baseline is a fatal error, so this exits the process on failure.
+///
+/// Arguments:
+/// * `baseline_file` - path to the JSON summary.
+pub fn read_baseline(baseline_file: &str) -> Baseline {
+    let content = match fs::read_to_string(baseline_file) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("Can't read baseline {}: {}", baseline_file, err);
+            process::exit(1);
+        }
+    };
+
+    let summary: JsonSummary = match serde_json::from_str(&content) {
+        Ok(summary) => summary,
+        Err(err) => {
+            eprintln!("Invalid baseline {}: {}", baseline_file, err);
+            process::exit(1);
+        }
+    };
+
+    return Baseline {
+        totals: summary.totals,
+        suites: summary.suites.into_iter().map(|suite| (suite.name, suite.counts)).collect(),
+    };
+}
+
+/// Formats change of the count since the baseline, e.g. ` (+2)`.
+/// Nothing is added if there's no baseline count or it didn't change.
+///
+/// Arguments:
+/// * `count` - current count.
+/// * `previous` - count in the baseline, if there's one.
+pub fn format_change(count: u64, previous: Option<u64>) -> String {
+    match previous {
+        Some(previous) if count > previous => return format!(" (+{})", format_count(count - previous)),
+        Some(previous) if count < previous => return format!(" (-{})", format_count(previous - count)),
+        _ => return String::new(),
+    }
+}
+
+/// Changes of each count of the suite or the whole report since the baseline, see `format_change`.
+/// All of them are empty if there's no baseline.
+pub struct CountChanges {
+    pub tests: String,
+    pub success: String,
+    pub skipped: String,
+    pub disabled: String,
+    pub failed: String,
+    pub errors: String,
+    /// Change of failed tests without ones with errors
+    pub failures: String,
+}
+
+impl CountChanges {
+    /// Computes changes of the counts.
+    ///
+    /// Arguments:
+    /// * `totals` - current counts.
+    /// * `previous` - counts in the baseline, if there are ones.
+    pub fn new(totals: &ReportTotals, previous: Option<&JsonCounts>) -> Self {
+        return CountChanges {
+            tests: format_change(totals.tests, previous.map(|counts| counts.tests)),
+            success: format_change(totals.success, previous.map(|counts| counts.success)),
+            skipped: format_change(totals.skipped, previous.map(|counts| counts.skipped)),
+            disabled: format_change(totals.disabled, previous.map(|counts| counts.disabled)),
+            failed: format_change(totals.failed, previous.map(|counts| counts.failed)),
+            errors: format_change(totals.errors, previous.and_then(|counts| counts.errors)),
+            failures: format_change(totals.failed - totals.errors,
+                                    previous.and_then(|counts| counts.errors.map(|errors| counts.failed.saturating_sub(errors)))),
+        };
+    }
+}
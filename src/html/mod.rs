@@ -0,0 +1,348 @@
+use junit2md::lang_specific::*;
+use junit2md::md::*;
+use junit2md::model::*;
+
+use crate::{failure_anchor, format_short_name, format_suite_start};
+use crate::options::*;
+use crate::render::*;
+use crate::totals::*;
+
+/// Style of the fragment, just enough for tables to be readable without a surrounding page.
+/// It only applies to tables of the report, so the page the fragment is embedded into keeps its look.
+const STYLE: &str = "<style>\n\
+                     table.junit2md { border-collapse: collapse; }\n\
+                     table.junit2md th, table.junit2md td { border: 1px solid #999; padding: 2px 8px; text-align: center; }\n\
+                     table.junit2md td:first-child { text-align: left; }\n\
+                     </style>\n";
+
+/// Cells of totals tables, see `TotalsCells`
+const TOTALS_CELLS: TotalsCells = TotalsCells {
+    escape: escape_html,
+    marker: "⚠",
+    emphasize: |label| format!("<b>{}</b>", label),
+    link: |text, anchor| format!("<a href=\"#{}\">{}</a>", anchor, text),
+};
+
+/// Formatting of testcase breakdown table cells in HTML
+const TESTCASE_CELLS: TestcaseCells = TestcaseCells {
+    escape: escape_html,
+    failure_ref: |fail_index, anchor| match anchor {
+        Some(anchor) => format!("<a href=\"#{}\">[{}]</a>", anchor, fail_index),
+        None => format!("[{}]", fail_index),
+    },
+    // rows are anchored by their id instead, see `create_table`
+    anchor: |_| String::new(),
+};
+
+/// Renders the report as self-contained HTML fragment with the same content as the Markdown report
+pub struct HtmlRenderer<'a> {
+    html: String,
+    opts: &'a ReportOptions,
+}
+
+impl<'a> HtmlRenderer<'a> {
+    pub fn new(opts: &'a ReportOptions) -> Self {
+        return HtmlRenderer { html: STYLE.to_owned(), opts };
+    }
+}
+
+impl ReportRenderer for HtmlRenderer<'_> {
+    fn header(&mut self, title: &str) {
+        self.html.push_str(&format!("<h1>{}</h1>\n", escape_html(title)));
+    }
+
+    fn health_badge(&mut self, badge: &str) {
+        self.html.push_str(&format!("<p>{}</p>\n", badge));
+    }
+
+    fn summary_line(&mut self, summary: &str) {
+        self.html.push_str(&format!("<p>{}</p>\n", summary));
+    }
+
+    fn suite_status(&mut self, status: &str) {
+        self.html.push_str(&format!("<p>{}</p>\n", escape_html(status)));
+    }
+
+    fn no_suites(&mut self) {
+        self.html.push_str("<p>No test suites found</p>\n");
+    }
+
+    fn suite_properties(&mut self, suite: &TestSuite) {
+        add_suite_properties(&mut self.html, suite, self.opts);
+    }
+
+    fn report_properties(&mut self, label: &str, properties: &[&TestProperty]) {
+        add_properties_list(&mut self.html, label, properties);
+    }
+
+    fn table_of_contents(&mut self, entries: &[TocEntry]) {
+        self.html.push_str("<ul>\n");
+        for entry in entries {
+            let name = escape_html(entry.suite.short_name(&self.opts.lang));
+            let link = match entry.first_failure {
+                Some(fail_index) => format!("<a href=\"#{}\">{}</a>", failure_anchor(fail_index), name),
+                None => name,
+            };
+            match entry.failed {
+                0 => self.html.push_str(&format!("<li>{} (no failures)</li>\n", link)),
+                _ => self.html.push_str(&format!("<li>{} ({})</li>\n", link, pluralize(entry.failed, "failure"))),
+            }
+        }
+        self.html.push_str("</ul>\n");
+    }
+
+    fn section(&mut self, title: &str) {
+        self.html.push_str(&format!("<h2>{}</h2>\n", title));
+    }
+
+    fn suite_totals(&mut self, suite: &TestSuite) {
+        create_table(&mut self.html, suite_totals_rows(suite, self.opts, &TOTALS_CELLS), &[]);
+
+        if !count_success(suite).1 {
+            add_inconsistent_totals_note(&mut self.html, &[suite], self.opts);
+        }
+    }
+
+    fn totals(&mut self, suites: &[TestSuite], totals: &ReportTotals) {
+        create_table(&mut self.html, totals_rows(suites, totals, self.opts, &TOTALS_CELLS), &[]);
+
+        let inconsistent: Vec<&TestSuite> = suites.iter().filter(|suite| !count_success(suite).1).collect();
+        if !inconsistent.is_empty() {
+            add_inconsistent_totals_note(&mut self.html, &inconsistent, self.opts);
+        }
+    }
+
+    fn baseline_suites(&mut self, added: &[&str], removed: &[&str]) {
+        let escape = |names: &[&str]| names.iter().map(|name| escape_html(name)).collect::<Vec<String>>().join(", ");
+        if !added.is_empty() {
+            self.html.push_str(&format!("<p>New suites since baseline: {}</p>\n", escape(added)));
+        }
+        if !removed.is_empty() {
+            self.html.push_str(&format!("<p>Removed suites since baseline: {}</p>\n", escape(removed)));
+        }
+    }
+
+    fn testcase_summary(&mut self, rows: &[TestcaseRow]) {
+        let row_ids: Vec<Option<String>> = rows.iter().map(|row| row.anchor.clone()).collect();
+        let table = testcase_rows(rows, self.opts, &TESTCASE_CELLS, |name| format_short_name(name, &self.opts.lang, escape_html));
+        create_table(&mut self.html, table, &row_ids);
+    }
+
+    fn durations(&mut self, sparkline: &str) {
+        self.html.push_str(&format!("<p>Test durations: {}</p>\n", sparkline));
+    }
+
+    fn test_output(&mut self, test: &TestCase) {
+        self.html.push_str(&format!("<h3>{}</h3>\n", escape_html(&test.name)));
+
+        if let Some(out) = &test.system_out {
+            add_code_section(&mut self.html, self.opts.labels.stdout(), "Test stdout", out, self.opts);
+        }
+
+        if let Some(err) = &test.system_err {
+            add_code_section(&mut self.html, self.opts.labels.stderr(), "Test stderr", err, self.opts);
+        }
+    }
+
+    fn failure_detail(&mut self, failure: &FailureDetail) {
+        add_failure_detail(&mut self.html, failure, self.opts);
+    }
+
+    fn flaky_test(&mut self, test: &TestCase) {
+        self.html.push_str(&format!("<h3>{}</h3>\n", escape_html(&test.name)));
+        for (index, rerun) in test.rerun_results().enumerate() {
+            let message = rerun.message.as_deref().unwrap_or("Not specified");
+            self.html.push_str(&format!("<p>Failed run {}: <code>{}</code></p>\n", index + 1, escape_html(message)));
+            if let Some(stack_trace) = &rerun.stack_trace {
+                add_code_section(&mut self.html, self.opts.labels.details(), "Details", stack_trace, self.opts);
+            }
+        }
+    }
+
+    fn omitted_failures(&mut self, count: u64) {
+        self.html.push_str(&format!("<p>... and {} (not shown)</p>\n", pluralize(count, "more failure")));
+    }
+
+    fn class_summary(&mut self, classes: &[ClassCounts]) {
+        let rows = class_counts_rows(classes, |name| format_short_name(name, &self.opts.lang, escape_html));
+        create_table(&mut self.html, rows, &[]);
+    }
+
+    fn skipped_list(&mut self, skipped: &[SkippedTest]) {
+        let mut table = vec![vec!["Testcase name".to_owned(), "Reason".to_owned()]];
+        for SkippedTest { test, reason } in skipped {
+            table.push(vec![escape_html(strip_namespace(&test.name, &self.opts.lang)), escape_html(reason)]);
+        }
+
+        create_table(&mut self.html, table, &[]);
+    }
+
+    fn input_source(&mut self, path: &str, source: &str) {
+        self.html.push_str(&format!("<details>\n<summary>{}</summary>\n<pre>{}</pre>\n</details>\n",
+            escape_html(path), escape_html(normalize_newlines(source).trim_end_matches('\n'))));
+    }
+
+    fn footer(&mut self, footer: &str) {
+        self.html.push_str(&format!("<p><i>{}</i></p>\n", escape_html(footer)));
+    }
+
+    fn finish(self) -> String {
+        return self.html;
+    }
+}
+
+/// Escapes characters that have special meaning in HTML, so user-provided text is shown verbatim.
+///
+/// Arguments:
+/// * `text` - text to escape.
+fn escape_html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(c),
+        }
+    }
+    return result;
+}
+
+/// Creates table with a header row.
+///
+/// Arguments:
+/// * `html` - document to append the table to.
+/// * `rows` - table rows, top-to-down. Cells are added as is, they should be escaped already.
+/// * `row_ids` - ids of value rows, rows without one are left without id.
+fn create_table(html: &mut String, rows: Vec<Vec<String>>, row_ids: &[Option<String>]) {
+    if rows.len() < 2 {
+        // we need at least one header row and one value row
+        return;
+    }
+
+    html.push_str("<table class=\"junit2md\">\n<thead>\n<tr>");
+    for cell in &rows[0] {
+        html.push_str(&format!("<th>{}</th>", cell));
+    }
+    html.push_str("</tr>\n</thead>\n<tbody>\n");
+
+    for (index, row) in rows.iter().skip(1).enumerate() {
+        match row_ids.get(index) {
+            Some(Some(id)) => html.push_str(&format!("<tr id=\"{}\">", id)),
+            _ => html.push_str("<tr>"),
+        }
+        for cell in row {
+            html.push_str(&format!("<td>{}</td>", cell));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n");
+}
+
+/// Adds start time, host and properties of the suite.
+///
+/// Arguments:
+/// * `html` - document to append to.
+/// * `suite` - test suite to get properties from.
+/// * `opts` - report options.
+fn add_suite_properties(html: &mut String, suite: &TestSuite, opts: &ReportOptions) {
+    if let (Some(timestamp), Some(hostname), Some(time)) = (&suite.timestamp, &suite.hostname, &suite.time) {
+        let timestamp = format_suite_start(timestamp, opts);
+        html.push_str(&format!("<p>Testset was started on host {} at {} and took {} seconds to finish.</p>\n",
+            escape_html(hostname), escape_html(&timestamp), escape_html(time)));
+    }
+
+    if let Some(properties) = &suite.properties {
+        add_properties_list(html, "Properties:", &properties.properties.iter().collect::<Vec<_>>());
+    }
+}
+
+/// Adds labeled list of properties, multi-line values keep their line breaks.
+///
+/// Arguments:
+/// * `html` - document to append to.
+/// * `label` - paragraph shown above the list.
+/// * `properties` - properties to list.
+fn add_properties_list(html: &mut String, label: &str, properties: &[&TestProperty]) {
+    html.push_str(&format!("<p>{}</p>\n<ul>\n", label));
+    for prop in properties {
+        let value = prop.value.as_deref().unwrap_or_default();
+        if value.contains('\n') {
+            // multi-line values keep their line breaks
+            html.push_str(&format!("<li>{}:<pre>{}</pre></li>\n", escape_html(&prop.name), escape_html(&normalize_newlines(value))));
+            continue;
+        }
+
+        html.push_str(&format!("<li>{}: {}</li>\n", escape_html(&prop.name), escape_html(value)));
+    }
+    html.push_str("</ul>\n");
+}
+
+/// Adds details of a failed test, its header is the anchor the breakdown table links to.
+///
+/// Arguments:
+/// * `html` - document to append to.
+/// * `failure` - failed test to report.
+/// * `opts` - report options.
+fn add_failure_detail(html: &mut String, failure: &FailureDetail, opts: &ReportOptions) {
+    let FailureDetail { index, suite_name, ref suite_anchor, test, result } = *failure;
+    if let Some(suite_anchor) = suite_anchor {
+        html.push_str(&format!("<a id=\"{}\"></a>\n", suite_anchor));
+    }
+    html.push_str(&format!("<h3 id=\"{}\">{}</h3>\n", failure_anchor(index), escape_html(&test.name)));
+    html.push_str("<ul>\n");
+
+    if let Some(suite_name) = suite_name {
+        html.push_str(&format!("<li>Suite: {}</li>\n", escape_html(suite_name)));
+    }
+
+    if let Some(classname) = &test.classname {
+        html.push_str(&format!("<li>Classname: {}</li>\n", format_short_name(classname, &opts.lang, escape_html)));
+    }
+
+    match failure.reason() {
+        (label, Some(message)) => html.push_str(&format!("<li>{}: <code>{}</code></li>\n", label, escape_html(message))),
+        (label, None) => html.push_str(&format!("<li>{}</li>\n", label)),
+    }
+    html.push_str("</ul>\n");
+
+    if let Some(body) = &result.body {
+        add_code_section(html, opts.labels.details(), "Details", body, opts);
+    }
+
+    if opts.verbose {
+        if let Some(out) = &test.system_out {
+            add_code_section(html, opts.labels.stdout(), "Test stdout", out, opts);
+        }
+
+        if let Some(err) = &test.system_err {
+            add_code_section(html, opts.labels.stderr(), "Test stderr", err, opts);
+        }
+    }
+}
+
+/// Adds long text, such as failure body or test output, as preformatted block.
+/// It's wrapped into `<details>` by default or labeled with a bold line if collapsing is turned off.
+///
+/// Arguments:
+/// * `html` - document to append to.
+/// * `summary` - summary of the collapsible section.
+/// * `label` - label shown above the block when it's not collapsed.
+/// * `code` - the text to add.
+/// * `opts` - report options.
+fn add_code_section(html: &mut String, summary: &str, label: &str, code: &str, opts: &ReportOptions) {
+    let code = prepare_code(code, opts);
+    let block = format!("<pre>{}</pre>", escape_html(code.trim_end_matches('\n')));
+    if opts.collapse {
+        html.push_str(&format!("<details>\n<summary>{}</summary>\n{}\n</details>\n", escape_html(summary), block));
+    } else {
+        html.push_str(&format!("<p><b>{}:</b></p>\n{}\n", label, block));
+    }
+}
+
+/// Adds note explaining the inconsistent totals marker.
+fn add_inconsistent_totals_note(html: &mut String, suites: &[&TestSuite], opts: &ReportOptions) {
+    html.push_str(&format!("<p>⚠ {}</p>\n", inconsistent_totals_note(suites, opts, &TOTALS_CELLS)));
+}
@@ -0,0 +1,559 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::process;
+use std::rc::Rc;
+use std::slice;
+use std::time::Instant;
+
+use indicatif::ProgressBar;
+use serde_xml_rs::from_reader;
+use serde_xml_rs::Error as XmlError;
+use zip::ZipArchive;
+use zip::result::ZipError;
+
+use junit2md::model::*;
+use junit2md::xunit::*;
+use junit2md::testng::*;
+
+use crate::{warn, PhaseTimings};
+use crate::options::ReportOptions;
+
+/// Parsed input files, ready to be reported
+pub enum InputReport {
+    /// Single test suite, reported in detail
+    Single(TestSuite),
+    /// Multiple test suites, reported briefly
+    Aggregated(JunitReport),
+}
+
+impl InputReport {
+    /// Returns all test suites of the report
+    pub fn suites(&self) -> &[TestSuite] {
+        match self {
+            InputReport::Single(suite) => return slice::from_ref(suite),
+            InputReport::Aggregated(report) => return &report.testsuites,
+        }
+    }
+
+    /// Returns all test suites of the report, for modification
+    pub fn suites_mut(&mut self) -> &mut [TestSuite] {
+        match self {
+            InputReport::Single(suite) => return slice::from_mut(suite),
+            InputReport::Aggregated(report) => return &mut report.testsuites,
+        }
+    }
+}
+
+/// Parses JUnit files.
+/// Single file is reported in detail, unless it's an aggregated report.
+/// Multiple files are always reported as aggregated.
+///
+/// Arguments:
+/// * `junit_files` - paths to JUnit XML files.
+/// * `opts` - report options.
+/// * `timings` - timings to add time spent reading files to.
+pub fn parse_junit_files(junit_files: &[&str], opts: &ReportOptions, timings: &mut PhaseTimings) -> (InputReport, usize) {
+    // Unfortunately, serde-xml-rs doesn't fully support enum
+    // decoding (or maybe I couldn't get it to work),
+    // so documents are told apart by their root element instead, see `parse_junit_documents`
+    let (mut documents, parse_errors) = parse_input_files(junit_files, "JUnit", opts, timings, parse_junit_documents);
+
+    if junit_files.len() == 1 && !is_zip_file(junit_files[0]) && documents.len() == 1 && parse_errors == 0 {
+        // it's a single file with a single document, report it as it is
+        match documents.pop().unwrap() {
+            JunitDocument::Aggregated(report) => return (InputReport::Aggregated(report), 0),
+            JunitDocument::Single(suite) => return (InputReport::Single(suite), 0),
+        }
+    }
+
+    // there are multiple files or documents, report them as aggregated
+    let testsuites = documents.into_iter()
+        .flat_map(|document| match document {
+            JunitDocument::Aggregated(report) => report.testsuites,
+            JunitDocument::Single(suite) => vec![suite],
+        })
+        .collect();
+    return (InputReport::Aggregated(JunitReport { testsuites, ..Default::default() }), parse_errors);
+}
+
+/// Parsed JUnit XML document, its root element tells which one it is
+enum JunitDocument {
+    /// `<testsuites>` root with any number of suites
+    Aggregated(JunitReport),
+    /// `<testsuite>` root
+    Single(TestSuite),
+}
+
+/// Parses a JUnit file. It may be a single suite, an aggregated report,
+/// or several such documents concatenated together, see `ReportSource::documents`.
+///
+/// Returns result of parsing for each document.
+///
+/// Arguments:
+/// * `source` - the file to parse.
+fn parse_junit_documents(source: &ReportSource) -> Vec<Result<JunitDocument, XmlError>> {
+    return source.documents().into_iter()
+        .map(|document| {
+            // serde-xml-rs ignores the root name, so either of them would parse
+            if document.root_name.as_deref() != Some("testsuites") {
+                return from_reader(document.source.open()).map(JunitDocument::Single);
+            }
+
+            // even without any suites, e.g. <testsuites/>
+            let mult: Result<JunitReport, XmlError> = from_reader(document.source.open());
+            return mult.map(JunitDocument::Aggregated).or_else(|err| {
+                // some tools write totals only on the root, then the whole report is one suite,
+                // the first attempt consumed the reader, so the document is read again
+                return from_reader(document.source.open()).map(JunitDocument::Single).map_err(|_| err);
+            });
+        })
+        .collect();
+}
+
+/// Reads and parses input files of any format, showing progress for multiple ones.
+/// ZIP archives are expanded into the reports they contain.
+/// Files that can be read only once, e.g. /dev/stdin, are supported too, see `open_report_file`.
+/// Documents that can't be parsed are reported and skipped, unless there's nothing else
+/// to report, i.e. the only input file is a single document, then it's a fatal error.
+///
+/// Returns parsed documents and number of documents that couldn't be parsed.
+///
+/// Arguments:
+/// * `input_files` - paths to input files.
+/// * `format_name` - name of the input format to show in warnings.
+/// * `opts` - report options.
+/// * `timings` - timings to add time spent reading files to.
+/// * `parse_documents` - parses a file into its documents.
+fn parse_input_files<T, F>(input_files: &[&str], format_name: &str, opts: &ReportOptions, timings: &mut PhaseTimings, parse_documents: F) -> (Vec<T>, usize)
+where
+    F: Fn(&ReportSource) -> Vec<Result<T, XmlError>>,
+{
+    let progress = create_progress_bar(input_files.len() as u64, opts);
+    let mut documents = vec![];
+    let mut parse_errors = 0;
+    for input_file in input_files {
+        let read_start = Instant::now();
+        let reports = if is_zip_file(input_file) {
+            progress.suspend(|| read_zip_reports(input_file, opts))
+        } else {
+            vec![(input_file.to_string(), open_report_file(input_file))]
+        };
+        timings.io += read_start.elapsed();
+
+        for (report_name, source) in reports {
+            let results = parse_documents(&source);
+            let only_document = input_files.len() == 1 && !is_zip_file(input_file) && results.len() == 1;
+            for result in results {
+                match result {
+                    Ok(document) => documents.push(document),
+                    Err(err) if only_document => {
+                        // nothing to report, this is fatal
+                        eprintln!("Couldn't parse {} XML: {}", format_name, err);
+                        process::exit(1);
+                    }
+                    Err(err) => {
+                        progress.suspend(|| warn(opts, &format!("Couldn't parse {} XML {}: {}", format_name, report_name, err)));
+                        parse_errors += 1;
+                    }
+                }
+            }
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    return (documents, parse_errors);
+}
+
+/// Input report, which can be read from the beginning again, e.g. to try another root element type.
+#[derive(Clone)]
+enum ReportSource {
+    /// Regular file, or a part of it, reopened for each read, so it's never held in memory as a whole
+    File { path: String, range: Option<Range<u64>> },
+    /// Content of a pipe or ZIP archive entry, which can be read only once, so it's kept in memory
+    Buffer { content: Rc<[u8]>, range: Range<usize> },
+}
+
+/// XML document found in a report, see `ReportSource::documents`
+struct XmlDocument {
+    source: ReportSource,
+    /// Local name of the root element, without namespace prefix
+    root_name: Option<String>,
+}
+
+impl ReportSource {
+    /// Creates source of the whole content read into memory.
+    fn buffer(content: Vec<u8>) -> ReportSource {
+        let len = content.len();
+        return ReportSource::Buffer { content: content.into(), range: 0..len };
+    }
+
+    /// Opens the report for buffered reading from its beginning.
+    /// Unreadable file is a fatal error, so this exits the process on failure.
+    fn open(&self) -> Box<dyn BufRead + '_> {
+        match self {
+            ReportSource::Buffer { content, range } => return Box::new(&content[range.clone()]),
+            ReportSource::File { path, range } => {
+                let file = fs::File::open(path).and_then(|mut file| {
+                    if let Some(range) = range {
+                        file.seek(SeekFrom::Start(range.start))?;
+                    }
+                    return Ok(file);
+                });
+                match file {
+                    Ok(file) => {
+                        let limit = range.as_ref().map_or(u64::MAX, |range| range.end - range.start);
+                        return Box::new(BufReader::new(file.take(limit)));
+                    }
+                    Err(err) => {
+                        eprintln!("Can't read JUnit file {}: {}", path, err);
+                        process::exit(1);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns source of a part of the report.
+    ///
+    /// Arguments:
+    /// * `start` - offset of the part from the beginning of the report.
+    /// * `end` - offset of the end of the part, exclusive.
+    fn part(&self, start: usize, end: usize) -> ReportSource {
+        match self {
+            ReportSource::File { path, range } => {
+                let base = range.as_ref().map_or(0, |range| range.start);
+                return ReportSource::File { path: path.clone(), range: Some(base + start as u64..base + end as u64) };
+            }
+            ReportSource::Buffer { content, range } => {
+                return ReportSource::Buffer { content: content.clone(), range: range.start + start..range.start + end };
+            }
+        }
+    }
+
+    /// Finds XML documents concatenated together in the report, each starting with its root element.
+    /// Declarations, comments and CDATA sections are skipped while looking for root elements,
+    /// so their content can't start a new document. The report is scanned a chunk at a time, not read in whole.
+    /// Report with one root element is returned as is, same as one that can't be scanned,
+    /// which has no root name then, so the parser reports what's wrong with it.
+    fn documents(&self) -> Vec<XmlDocument> {
+        let mut scanner = XmlRootScanner::default();
+        let mut reader = self.open();
+        loop {
+            let chunk_len = match reader.fill_buf() {
+                Ok(chunk) if !chunk.is_empty() => {
+                    scanner.feed(chunk);
+                    chunk.len()
+                }
+                // read errors are reported by the parser
+                _ => break,
+            };
+            reader.consume(chunk_len);
+        }
+
+        let roots = scanner.finish().unwrap_or_default();
+        if roots.len() > 1 {
+            return roots.into_iter()
+                .map(|root| XmlDocument { source: self.part(root.start, root.end), root_name: Some(root.name) })
+                .collect();
+        }
+
+        return vec![XmlDocument { source: self.clone(), root_name: roots.into_iter().next().map(|root| root.name) }];
+    }
+}
+
+/// Kind of markup the scanner is inside of, see `XmlRootScanner`
+#[derive(Clone, Copy, Default, PartialEq)]
+enum XmlMarkup {
+    /// Text between markup
+    #[default]
+    Text,
+    /// Just started with `<`, not known yet what it is
+    Unknown,
+    /// Start or self-closing tag of an element
+    StartTag,
+    /// Closing tag of an element
+    EndTag,
+    /// `<?...?>` or `<!...>` declaration, ends at `>` like tags
+    Declaration,
+    /// `<!-- ... -->`
+    Comment,
+    /// `<![CDATA[ ... ]]>`
+    CData,
+}
+
+/// Root element found by `XmlRootScanner`
+struct XmlRoot {
+    /// Offset of its start tag
+    start: usize,
+    /// Offset right after its end tag, or the start tag if it's self-closing
+    end: usize,
+    /// Local name, without namespace prefix
+    name: String,
+}
+
+/// Finds root elements in XML content, which can be fed a chunk at a time.
+/// Declarations, comments and CDATA sections can't start or end elements,
+/// neither can `>` inside quoted attribute values.
+#[derive(Default)]
+struct XmlRootScanner {
+    markup: XmlMarkup,
+    /// Beginning of the current markup, up to the point it's known what it is
+    prefix: Vec<u8>,
+    /// Quote character of the attribute value we're in, if any
+    quote: Option<u8>,
+    /// Last two bytes seen, to find terminators of comments, CDATA and self-closing tags
+    last: [u8; 2],
+    /// Name of the root start tag read so far, names of nested tags aren't needed
+    name: Vec<u8>,
+    reading_name: bool,
+    /// Offset of the next byte to be fed
+    offset: usize,
+    markup_start: usize,
+    root_start: usize,
+    root_name: String,
+    depth: usize,
+    roots: Vec<XmlRoot>,
+    malformed: bool,
+}
+
+impl XmlRootScanner {
+    /// Scans next chunk of the content.
+    fn feed(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            if !self.malformed {
+                self.scan(byte);
+            }
+            self.last = [self.last[1], byte];
+            self.offset += 1;
+        }
+    }
+
+    /// Scans a single byte at the current offset.
+    fn scan(&mut self, byte: u8) {
+        match self.markup {
+            XmlMarkup::Text => {
+                if byte == b'<' {
+                    self.markup = XmlMarkup::Unknown;
+                    self.markup_start = self.offset;
+                    self.prefix.clear();
+                    self.prefix.push(byte);
+                    self.name.clear();
+                    self.reading_name = self.depth == 0;
+                }
+            }
+            XmlMarkup::Unknown => {
+                self.prefix.push(byte);
+                self.markup = match self.prefix.as_slice() {
+                    b"<!--" => XmlMarkup::Comment,
+                    b"<![CDATA[" => XmlMarkup::CData,
+                    prefix if b"<!--".starts_with(prefix) || b"<![CDATA[".starts_with(prefix) => XmlMarkup::Unknown,
+                    [_, b'!', ..] | [_, b'?', ..] => XmlMarkup::Declaration,
+                    [_, b'/', ..] => XmlMarkup::EndTag,
+                    _ => XmlMarkup::StartTag,
+                };
+                if let XmlMarkup::StartTag | XmlMarkup::EndTag | XmlMarkup::Declaration = self.markup {
+                    // this byte may already be a quote or the end of the tag
+                    self.scan(byte);
+                }
+            }
+            XmlMarkup::Comment => {
+                if byte == b'>' && self.last == *b"--" {
+                    self.markup = XmlMarkup::Text;
+                }
+            }
+            XmlMarkup::CData => {
+                if byte == b'>' && self.last == *b"]]" {
+                    self.markup = XmlMarkup::Text;
+                }
+            }
+            XmlMarkup::StartTag | XmlMarkup::EndTag | XmlMarkup::Declaration => {
+                if self.markup == XmlMarkup::StartTag && self.reading_name {
+                    if byte.is_ascii_whitespace() || byte == b'/' || byte == b'>' {
+                        self.reading_name = false;
+                    } else {
+                        self.name.push(byte);
+                    }
+                }
+                match (self.quote, byte) {
+                    (None, b'"') | (None, b'\'') => self.quote = Some(byte),
+                    (Some(open), _) if open == byte => self.quote = None,
+                    (None, b'>') => self.end_markup(),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Handles markup that ends at the current offset.
+    fn end_markup(&mut self) {
+        let markup = std::mem::take(&mut self.markup);
+        let markup_end = self.offset + 1;
+        match markup {
+            XmlMarkup::EndTag => {
+                if self.depth == 0 {
+                    // closing tag without opening one, let the parser report it
+                    self.malformed = true;
+                    return;
+                }
+                self.depth -= 1;
+            }
+            XmlMarkup::StartTag => {
+                if self.depth == 0 {
+                    self.root_start = self.markup_start;
+                    let name = String::from_utf8_lossy(&self.name);
+                    self.root_name = name.rsplit(':').next().unwrap_or_default().to_owned();
+                }
+                if self.last[1] != b'/' {
+                    self.depth += 1;
+                }
+            }
+            _ => return,
+        }
+
+        if self.depth == 0 {
+            // root element just ended, either closed or self-closing
+            self.roots.push(XmlRoot { start: self.root_start, end: markup_end, name: std::mem::take(&mut self.root_name) });
+        }
+    }
+
+    /// Returns root elements found, or nothing if the content is malformed.
+    fn finish(self) -> Option<Vec<XmlRoot>> {
+        if self.malformed || self.markup != XmlMarkup::Text {
+            // unterminated markup, let the parser report it
+            return None;
+        }
+
+        return Some(self.roots);
+    }
+}
+
+/// Parses xUnit.net files.
+/// Each test collection becomes a separate suite. If there's only one collection in total,
+/// it's reported in detail, otherwise report is aggregated, same as for JUnit.
+///
+/// Arguments:
+/// * `xunit_files` - paths to xUnit.net XML files.
+/// * `opts` - report options.
+/// * `timings` - timings to add time spent reading files to.
+pub fn parse_xunit_files(xunit_files: &[&str], opts: &ReportOptions, timings: &mut PhaseTimings) -> (InputReport, usize) {
+    let (assemblies, parse_errors) = parse_input_files(xunit_files, "xUnit.net", opts, timings, |source| {
+        return vec![from_reader(source.open()).map(XunitAssemblies::into_testsuites)];
+    });
+    return (suites_report(assemblies.into_iter().flatten().collect()), parse_errors);
+}
+
+/// Parses TestNG result files.
+/// Each `<test>` of each suite becomes a separate suite. If there's only one in total,
+/// it's reported in detail, otherwise report is aggregated, same as for JUnit.
+///
+/// Arguments:
+/// * `testng_files` - paths to `testng-results.xml` files.
+/// * `opts` - report options.
+/// * `timings` - timings to add time spent reading files to.
+pub fn parse_testng_files(testng_files: &[&str], opts: &ReportOptions, timings: &mut PhaseTimings) -> (InputReport, usize) {
+    let (results, parse_errors) = parse_input_files(testng_files, "TestNG", opts, timings, |source| {
+        return vec![from_reader(source.open()).map(TestngResults::into_testsuites)];
+    });
+    return (suites_report(results.into_iter().flatten().collect()), parse_errors);
+}
+
+/// Wraps suites converted from other formats into a report.
+/// If there's only one suite, it's reported in detail, otherwise report is aggregated.
+fn suites_report(mut testsuites: Vec<TestSuite>) -> InputReport {
+    if testsuites.len() == 1 {
+        return InputReport::Single(testsuites.pop().unwrap());
+    }
+
+    return InputReport::Aggregated(JunitReport { testsuites, ..Default::default() });
+}
+
+/// Opens input file for parsing. Regular files are streamed by the parser and reopened if needed,
+/// other ones, e.g. pipes like /dev/stdin, can be read only once, so they're read into memory right away.
+/// Unreadable file is a fatal error, so this exits the process on failure.
+///
+/// Arguments:
+/// * `junit_file` - path to the file to open.
+fn open_report_file(junit_file: &str) -> ReportSource {
+    let content = match fs::metadata(junit_file) {
+        Ok(metadata) if metadata.is_file() => return ReportSource::File { path: junit_file.to_owned(), range: None },
+        Ok(_) => fs::read(junit_file),
+        Err(err) => Err(err),
+    };
+
+    match content {
+        Ok(content) => return ReportSource::buffer(content),
+        Err(err) => {
+            eprintln!("Can't read JUnit file {}: {}", junit_file, err);
+            process::exit(1);
+        }
+    }
+}
+
+/// Checks whether the input file is a ZIP archive of reports, judging by its extension.
+fn is_zip_file(file: &str) -> bool {
+    return file.to_lowercase().ends_with(".zip");
+}
+
+/// Reads all XML reports from the ZIP archive, other entries are ignored.
+/// Entries are compressed and can't be reopened at an offset, so they're read into memory.
+/// Entries are returned as pairs of `archive.zip:entry.xml` name and content.
+/// Unreadable archive is a fatal error, so this exits the process on failure.
+/// Unreadable entries are reported and skipped.
+///
+/// Arguments:
+/// * `zip_file` - path to the archive to read.
+/// * `opts` - report options.
+fn read_zip_reports(zip_file: &str, opts: &ReportOptions) -> Vec<(String, ReportSource)> {
+    let archive = fs::File::open(zip_file)
+        .map_err(ZipError::from)
+        .and_then(ZipArchive::new);
+
+    let mut archive = match archive {
+        Ok(archive) => archive,
+        Err(err) => {
+            eprintln!("Can't read ZIP archive {}: {}", zip_file, err);
+            process::exit(1);
+        }
+    };
+
+    let mut reports = vec![];
+    for index in 0..archive.len() {
+        let mut entry = match archive.by_index(index) {
+            Ok(entry) => entry,
+            Err(err) => {
+                warn(opts, &format!("Can't read entry {} of ZIP archive {}: {}", index, zip_file, err));
+                continue;
+            }
+        };
+
+        if !entry.is_file() || !entry.name().to_lowercase().ends_with(".xml") {
+            continue;
+        }
+
+        let entry_name = format!("{}:{}", zip_file, entry.name());
+        let mut content = Vec::new();
+        match entry.read_to_end(&mut content) {
+            Ok(_) => reports.push((entry_name, ReportSource::buffer(content))),
+            Err(err) => warn(opts, &format!("Can't read JUnit file {}: {}", entry_name, err)),
+        }
+    }
+
+    return reports;
+}
+
+/// Creates progress bar for reading multiple files.
+/// The bar is drawn to stderr, so it's hidden if stderr is not a terminal or quiet mode is on.
+/// A single file is read in one step, so there's no progress to show for it either.
+///
+/// Arguments:
+/// * `file_count` - total number of files that will be read.
+/// * `opts` - report options to check quiet flag in.
+fn create_progress_bar(file_count: u64, opts: &ReportOptions) -> ProgressBar {
+    if file_count <= 1 || opts.quiet || !io::stderr().is_terminal() {
+        return ProgressBar::hidden();
+    }
+
+    return ProgressBar::new(file_count);
+}
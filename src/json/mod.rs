@@ -0,0 +1,112 @@
+use serde_derive::{Deserialize, Serialize};
+
+use junit2md::model::*;
+
+use crate::totals::*;
+
+/// Counts of each result of the whole report or a suite
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct JsonCounts {
+    pub tests: u64,
+    pub success: u64,
+    pub skipped: u64,
+    pub disabled: u64,
+    pub failed: u64,
+    /// Summaries written before errors were counted separately don't have it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errors: Option<u64>,
+}
+
+impl JsonCounts {
+    /// Takes counts from totals of the whole report or a suite
+    pub fn new(totals: &ReportTotals) -> Self {
+        return JsonCounts {
+            tests: totals.tests,
+            success: totals.success,
+            skipped: totals.skipped,
+            disabled: totals.disabled,
+            failed: totals.failed,
+            errors: Some(totals.errors),
+        };
+    }
+}
+
+/// Machine-readable summary of the report: totals and counts of each suite
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonSummary {
+    #[serde(flatten)]
+    pub totals: JsonCounts,
+    pub suites: Vec<JsonSuite>,
+}
+
+/// Counts of a single suite in the summary
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonSuite {
+    /// Full name of the suite
+    pub name: String,
+    #[serde(flatten)]
+    pub counts: JsonCounts,
+}
+
+/// Manifest of files written by `--split-output`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SplitManifest {
+    /// Path of the index file
+    pub index: String,
+    pub files: Vec<SplitManifestFile>,
+}
+
+/// Suite report in the manifest, with the same counts as in the report
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SplitManifestFile {
+    /// Path of the suite report
+    pub file: String,
+    /// Full name of the suite
+    pub name: String,
+    #[serde(flatten)]
+    pub counts: JsonCounts,
+}
+
+/// Renders machine-readable summary of the report: totals and counts of each suite.
+/// It can be read back as a baseline of the next run, see `read_baseline`.
+///
+/// Arguments:
+/// * `suites` - test suites to summarize.
+/// * `totals` - totals of the whole report.
+pub fn report_to_json(suites: &[TestSuite], totals: &ReportTotals) -> String {
+    let summary = JsonSummary {
+        totals: JsonCounts::new(totals),
+        suites: suites.iter()
+            .map(|suite| JsonSuite { name: suite.display_name().to_owned(), counts: JsonCounts::new(&compute_suite_totals(suite)) })
+            .collect(),
+    };
+
+    return to_json(&summary);
+}
+
+/// Renders manifest of files written by `--split-output`: paths of the index and of each suite report,
+/// with the suite name and the same counts as in the suite report.
+///
+/// Arguments:
+/// * `index_file` - path of the index file.
+/// * `suite_files` - paths of suite reports along with their suites.
+pub fn split_manifest_to_json(index_file: &str, suite_files: &[(String, &TestSuite)]) -> String {
+    let manifest = SplitManifest {
+        index: index_file.to_owned(),
+        files: suite_files.iter()
+            .map(|(file, suite)| SplitManifestFile {
+                file: file.to_owned(),
+                name: suite.display_name().to_owned(),
+                counts: JsonCounts::new(&compute_suite_totals(suite)),
+            })
+            .collect(),
+    };
+
+    return to_json(&manifest);
+}
+
+/// Renders the value as pretty-printed JSON document, ending with a newline.
+fn to_json<T: serde::Serialize>(value: &T) -> String {
+    // plain structs with string keys always serialize
+    return serde_json::to_string_pretty(value).expect("summary is serializable") + "\n";
+}
@@ -1,19 +1,118 @@
+use serde_derive::Deserialize;
+
+/// Language of the tested code. Names of its test classes and methods are shortened
+/// by removing the namespace, each language has its own idea of what a namespace looks like.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    /// `com.example.FooTest.should_do_x[1]`
+    Java,
+    /// Same as Java, but test names in backticks can contain spaces: `com.example.FooTest.should do x()`
+    Kotlin,
+    /// Nested classes are joined with `+`, parameters are in parentheses: `Example.Tests.Outer+Inner.Adds(1.5,2)`
+    Csharp,
+}
+
+impl Lang {
+    /// Removes namespace from a fully-qualified name of class or test, if the name looks like one of this language.
+    /// Returns `None` if it doesn't, or there's no namespace to remove.
+    ///
+    /// ```
+    /// use junit2md::lang_specific::Lang;
+    ///
+    /// assert_eq!(Lang::Java.strip_namespace("com.example.FooTest"), Some("FooTest"));
+    /// assert_eq!(Lang::Java.strip_namespace("Example.Tests.CalcTests.Adds(1.5,2)"), None);
+    /// assert_eq!(Lang::Csharp.strip_namespace("Example.Tests.CalcTests.Adds(1.5,2)"), Some("Adds(1.5,2)"));
+    /// assert_eq!(Lang::Csharp.strip_namespace("Example.Tests.Outer+Inner"), Some("Outer+Inner"));
+    /// assert_eq!(Lang::Kotlin.strip_namespace("com.example.FooTest.should do x()"), Some("should do x()"));
+    /// assert_eq!(Lang::Kotlin.strip_namespace("FooTest"), None);
+    /// ```
+    ///
+    /// Arguments:
+    /// * `name` - class or test name.
+    pub fn strip_namespace(self, name: &str) -> Option<&str> {
+        // parameters of parameterized test, e.g. `[1]` or `(a, b)`, may contain anything
+        let params_start = match self {
+            Lang::Java | Lang::Kotlin => trailing_group_start(name, '[', ']'),
+            Lang::Csharp => trailing_group_start(name, '(', ')'),
+        };
+        let qualified_name = &name[..params_start.unwrap_or(name.len())];
+
+        let last_dot_idx = qualified_name.rfind('.')?;
+        let (namespace, short_name) = (&qualified_name[..last_dot_idx], &qualified_name[last_dot_idx + 1..]);
+        let is_sensible = match self {
+            Lang::Java => namespace.split('.').all(is_identifier) && is_identifier(short_name),
+            Lang::Kotlin => {
+                // `should do x()` is how JUnit reports Kotlin functions with spaces in names
+                let short_name = short_name.strip_suffix("()").unwrap_or(short_name);
+                namespace.split('.').all(is_identifier) && !short_name.trim().is_empty()
+            }
+            Lang::Csharp => namespace.split('.').chain(short_name.split(['+', '`'])).all(is_identifier),
+        };
+
+        if is_sensible {
+            return Some(&name[last_dot_idx + 1..]);
+        }
+        return None;
+    }
+}
+
+/// Removes namespace from a fully-qualified name, using the first of the languages it looks like.
+/// If the name doesn't look like any of them, it's returned as is.
+///
+/// ```
+/// use junit2md::lang_specific::{strip_namespace, Lang};
+///
+/// let langs = [Lang::Java, Lang::Csharp];
+/// assert_eq!(strip_namespace("com.example.FooTest", &langs), "FooTest");
+/// assert_eq!(strip_namespace("Example.Tests.CalcTests.Adds(1.5,2)", &langs), "Adds(1.5,2)");
+/// assert_eq!(strip_namespace("Untitled suite in casper.js", &langs), "Untitled suite in casper.js");
+/// ```
+///
+/// Arguments:
+/// * `name` - class or test name.
+/// * `langs` - languages to try, in order.
+pub fn strip_namespace<'a>(name: &'a str, langs: &[Lang]) -> &'a str {
+    return langs.iter()
+        .find_map(|lang| lang.strip_namespace(name))
+        .unwrap_or(name);
+}
+
 /// Removes Java package from a fully-qualified class name.
 /// If class name doesn't contain package, does nothing.
-/// 
+/// Trailing `[...]` segment of parameterized tests is kept as is, even if it contains dots or spaces.
+///
+/// ```
+/// use junit2md::lang_specific::omit_java_package;
+///
+/// assert_eq!(omit_java_package("com.example.FooTest"), "FooTest");
+/// assert_eq!(omit_java_package("FooTest.test[with spaces]"), "test[with spaces]");
+/// assert_eq!(omit_java_package("FooTest.test[a, b]"), "test[a, b]");
+/// assert_eq!(omit_java_package("com.example.FooTest.should_do_x[param=1.5]"), "should_do_x[param=1.5]");
+/// assert_eq!(omit_java_package("Untitled suite in casper.js"), "Untitled suite in casper.js");
+/// ```
+///
 /// Arguments:
 /// * `name` - class name.
-pub(super) fn omit_java_package(name: &str) -> &str {
-    if !name.contains('.') || name.contains(' ') {
-        // not a java class name
-        return name;
+pub fn omit_java_package(name: &str) -> &str {
+    return strip_namespace(name, &[Lang::Java]);
+}
+
+/// Returns start of the trailing group enclosed in the given brackets, e.g. `[1]` of `test[1]`.
+fn trailing_group_start(name: &str, open: char, close: char) -> Option<usize> {
+    match name.find(open) {
+        Some(idx) if name.ends_with(close) => return Some(idx),
+        _ => return None,
     }
+}
 
-    let last_dot_idx = name.rfind('.').unwrap() + 1;
-    if name.len() == last_dot_idx {
-        // string was ending with dot? what is that?
-        return name;
+/// Checks whether the name segment can be an identifier: letters, digits, `_` and `$`, not starting with a digit.
+fn is_identifier(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    match chars.next() {
+        Some(first) if first.is_alphabetic() || first == '_' || first == '$' => {}
+        _ => return false,
     }
 
-    return name.get(last_dot_idx..name.len()).unwrap_or(name);
-}
\ No newline at end of file
+    return chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+}
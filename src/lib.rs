@@ -0,0 +1,8 @@
+//! Library part of junit2md: JUnit report model and helpers
+//! for generating Markdown out of it.
+
+pub mod model;
+pub mod md;
+pub mod lang_specific;
+pub mod xunit;
+pub mod testng;
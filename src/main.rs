@@ -1,239 +1,2312 @@
-mod model;
-mod md;
-mod lang_specific;
+mod options;
+mod totals;
+mod term;
+mod text;
+mod asciidoc;
+mod html;
+mod render;
+mod json;
+mod baseline;
+mod input;
 
+use std::cmp;
 use std::fs;
-use std::fmt::Display;
+use std::env;
+use std::collections::{BTreeMap, BTreeSet};
+use std::process;
+use std::ptr;
+use std::slice;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use core::sync::atomic::AtomicBool;
-use core::sync::atomic::Ordering;
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use clap::{Arg, App, ArgMatches};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use regex::Regex;
 
-use clap::{Arg, App};
-use serde_xml_rs::from_reader;
-use serde_xml_rs::Error as XmlError;
-
-use lang_specific::*;
-use model::*;
-use md::*;
-
-static IS_VERBOSE: AtomicBool = AtomicBool::new(false);
+use junit2md::lang_specific::*;
+use junit2md::model::*;
+use junit2md::md::*;
+use options::*;
+use totals::*;
+use term::*;
+use text::*;
+use asciidoc::*;
+use html::*;
+use json::*;
+use baseline::*;
+use input::*;
+use render::*;
 
 fn main() {
     let cli_args = App::new("JUnit 2 Markdown converter")
-                        .version("0.1.0")
+                        .version(env!("CARGO_PKG_VERSION"))
                         .author("Oleg `Kanedias` Chernovskiy <kanedias@keemail.me>")
                         .about("Generates Markdown text from JUnit XML report")
                         .arg(Arg::with_name("input-files")
                                 .multiple(true)
-                                .required(true)
+                                .required_unless("files-from")
                                 .help("Input JUnit XML(s) to generate Markdown from. \
                                        Generates verbose report in case there's single file. \
-                                       Generates brief report in case there are multiple files or it's an aggregated report."))
+                                       Generates brief report in case there are multiple files or it's an aggregated report. \
+                                       ZIP archives are unpacked and all XML files inside are reported as aggregated."))
                         .arg(Arg::with_name("verbose")
                                 .short("v")
                                 .required(false)
-                                .help("Verbose output (hostnames, properties, standard streams)"))
+                                .help("Verbose output (hostnames, properties, standard streams). Aggregated reports list properties of the whole run and ones shared by all suites. Can also be enabled with JUNIT2MD_VERBOSE=1"))
+                        .arg(Arg::with_name("relative-time")
+                                .long("relative-time")
+                                .help("Show how long ago the suite was started, e.g. \"2 hours ago\", next to its timestamp in verbose mode \
+                                       and in the --show-timestamps column"))
+                        .arg(Arg::with_name("exclude-skipped-from-rate")
+                                .long("exclude-skipped-from-rate")
+                                .help("Compute pass rate in aggregated report against tests that ran, \
+                                       not counting skipped and disabled ones"))
+                        .arg(Arg::with_name("skips-as-failures")
+                                .long("skips-as-failures")
+                                .help("Treat skipped and disabled tests as not passing: the health badge turns red, \
+                                       pass rate is always computed against all tests, overriding --exclude-skipped-from-rate, \
+                                       and --exit-policy uses the 'failed' code for them. There is no separate --fail-on-skip, \
+                                       use --exit-policy skipped=CODE to only change the exit code. \
+                                       Breakdown tables still show skipped tests as such"))
+                        .arg(Arg::with_name("trust-testcases")
+                                .long("trust-testcases")
+                                .help("Recompute counts of each suite and of the whole report from its test cases, \
+                                       ignoring tests, failures, errors, skipped and disabled attributes. \
+                                       Missing suite and report times are summed from test cases too. \
+                                       Useful for Bazel test.xml and other emitters with unreliable attributes"))
+                        .arg(Arg::with_name("show-timestamps")
+                                .long("show-timestamps")
+                                .help("Add column with start time of each suite to aggregated report, in UTC. \
+                                       With --relative-time it shows how long ago suites were started"))
+                        .arg(Arg::with_name("verbose-passing")
+                                .long("verbose-passing")
+                                .help("Show stdout and stderr of passed tests too, in a separate section of single suite report"))
+                        .arg(Arg::with_name("quiet")
+                                .short("q")
+                                .long("quiet")
+                                .required(false)
+                                .help("Don't print non-fatal warnings (e.g. unparseable files in aggregated report) \
+                                       and the final \"X passed, Y failed, Z skipped\" summary line to stderr"))
+                        .arg(Arg::with_name("bar-width")
+                                .long("bar-width")
+                                .takes_value(true)
+                                .value_name("CHARS")
+                                .validator(|value| value.parse::<usize>().map(|_| ()).map_err(|err| err.to_string()))
+                                .help("Width of the result bar shown for each suite in aggregated report, 20 by default. Use 0 to hide it"))
+                        .arg(Arg::with_name("columns")
+                                .long("columns")
+                                .takes_value(true)
+                                .value_name("LIST")
+                                .validator(|value| TotalsColumn::parse_list(&value).map(|_| ()))
+                                .help("Comma-separated columns of the totals table of aggregated report, in order, e.g. \
+                                       name,success,failures,errors,total,pass_rate. Available columns: name, time, started, success, \
+                                       skipped, disabled, failures, errors, total, percent (share of all tests), pass_rate, results. \
+                                       Failures don't include errors if there's an errors column. Overrides --no-percent \
+                                       and --show-timestamps, results bar is --bar-width wide"))
+                        .arg(Arg::with_name("width")
+                                .long("width")
+                                .takes_value(true)
+                                .value_name("CHARS")
+                                .validator(|value| match value.parse::<usize>() {
+                                    Ok(width) if width < 3 => Err("must be at least 3".to_owned()),
+                                    Ok(_) => Ok(()),
+                                    Err(err) => Err(err.to_string()),
+                                })
+                                .help("Make all columns of Markdown tables this wide, so column widths don't change between runs. \
+                                       Longer cells are shortened with an ellipsis, except ones with links, bold text or HTML tags"))
+                        .arg(Arg::with_name("title")
+                                .long("title")
+                                .takes_value(true)
+                                .value_name("TEXT")
+                                .help("Title of the report. Defaults to suite name for single report \
+                                       and \"Aggregated test report\" for aggregated one"))
+                        .arg(Arg::with_name("report-title-from-file")
+                                .long("report-title-from-file")
+                                .takes_value(true)
+                                .value_name("PATH")
+                                .help("Use the first non-empty line of the file as the title of single suite report instead of suite name, \
+                                       e.g. a README of the module. Leading # of a Markdown heading is removed. --title takes precedence"))
+                        .arg(Arg::with_name("no-header-counts")
+                                .long("no-header-counts")
+                                .help("Don't append number of tests to the report title"))
+                        .arg(Arg::with_name("flavor")
+                                .long("flavor")
+                                .takes_value(true)
+                                .value_name("FLAVOR")
+                                .possible_values(&["github", "gitlab"])
+                                .help("Markdown dialect to generate, github by default. \
+                                       Use gitlab for GitLab-compatible collapsible sections"))
+                        .arg(Arg::with_name("no-collapse")
+                                .long("no-collapse")
+                                .help("Show failure details and test output inline as code blocks instead of collapsible sections"))
+                        .arg(Arg::with_name("no-details")
+                                .long("no-details")
+                                .help("Don't add failure details section, report only totals and testcase breakdown"))
+                        .arg(Arg::with_name("no-percent")
+                                .long("no-percent")
+                                .help("Don't add percentage and pass rate columns to totals tables, report only counts"))
+                        .arg(Arg::with_name("files-from")
+                                .long("files-from")
+                                .takes_value(true)
+                                .value_name("FILE")
+                                .help("Read input file paths from FILE, one per line, in addition to the ones given as arguments. \
+                                       Use - to read them from stdin. Blank lines and lines starting with # are ignored"))
+                        .arg(Arg::with_name("validate")
+                                .long("validate")
+                                .help("Check reports for suspicious content, e.g. duplicate test names, and print warnings to stderr. \
+                                       Doesn't affect the report itself"))
+                        .arg(Arg::with_name("show-reported-status")
+                                .long("show-reported-status")
+                                .help("Add a column with test status as reported by the test framework (status attribute) to the testcase breakdown"))
+                        .arg(Arg::with_name("show-retries")
+                                .long("show-retries")
+                                .help("Add a column with number of reruns (retries or attempts attribute) to the testcase breakdown. \
+                                       Retried tests are flagged even if they passed in the end"))
+                        .arg(Arg::with_name("toc")
+                                .long("toc")
+                                .help("Add table of contents to aggregated report, listing suites with most failures first. \
+                                       Failing suites link to their first failure"))
+                        .arg(Arg::with_name("health-badge")
+                                .long("health-badge")
+                                .help("Add a red, yellow or green health indicator under the report title"))
+                        .arg(Arg::with_name("no-summary-line")
+                                .long("no-summary-line")
+                                .help("Don't add a sentence with counts of suites and test results under the aggregated report title"))
+                        .arg(Arg::with_name("suite-status")
+                                .long("suite-status")
+                                .help("Add a line with counts of passed, failed and skipped tests under the title \
+                                       of single suite report and of each suite file of --split-output. \
+                                       Counts are marked with status symbols"))
+                        .arg(Arg::with_name("sparkline")
+                                .long("sparkline")
+                                .help("Add a sparkline of test durations under the testcase breakdown"))
+                        .arg(Arg::with_name("row-anchors")
+                                .long("row-anchors")
+                                .help("Add an anchor to each row of the testcase breakdown, so tests can be linked to as #test-<name>"))
+                        .arg(Arg::with_name("show-classname")
+                                .long("show-classname")
+                                .help("Add a column with test class name to the testcase breakdown"))
+                        .arg(Arg::with_name("cause-type")
+                                .long("cause-type")
+                                .help("Show exception type without package next to the failure link in testcase breakdown"))
+                        .arg(Arg::with_name("strip-ansi")
+                                .long("strip-ansi")
+                                .help("Remove ANSI escape sequences, e.g. colors, from failure details and test output"))
+                        .arg(Arg::with_name("tail-output")
+                                .long("tail-output")
+                                .takes_value(true)
+                                .value_name("LINES")
+                                .validator(|value| match value.parse::<usize>() {
+                                    Ok(0) => Err("must be at least 1".to_owned()),
+                                    Ok(_) => Ok(()),
+                                    Err(err) => Err(err.to_string()),
+                                })
+                                .help("Only keep this many last lines of failure details, test output and embedded sources, \
+                                       that's where the cause of failure usually is"))
+                        .arg(Arg::with_name("max-failures")
+                                .long("max-failures")
+                                .takes_value(true)
+                                .value_name("COUNT")
+                                .validator(|value| value.parse::<usize>().map(|_| ()).map_err(|err| err.to_string()))
+                                .help("Only show details of this many first failures, the rest are counted in a note. \
+                                       Testcase breakdown still lists all of them, just without links"))
+                        .arg(Arg::with_name("list-skips")
+                                .long("list-skips")
+                                .help("Add a section listing skipped tests with reasons they were skipped"))
+                        .arg(Arg::with_name("show-flaky")
+                                .long("show-flaky")
+                                .help("Add a section with failed runs of tests that passed when rerun \
+                                       (flakyFailure, flakyError, rerunFailure and rerunError elements) to single suite report, \
+                                       and a row with their count to its totals. They are still counted as successful"))
+                        .arg(Arg::with_name("by-class")
+                                .long("by-class")
+                                .help("Add a section with total, passed, failed and skipped test counts of each class \
+                                       to single suite report, classes with most tests go first"))
+                        .arg(Arg::with_name("soft-break-width")
+                                .long("soft-break-width")
+                                .takes_value(true)
+                                .value_name("CHARS")
+                                .validator(|value| value.parse::<usize>().map(|_| ()).map_err(|err| err.to_string()))
+                                .help("Insert invisible soft breaks into test names in testcase breakdown \
+                                       every CHARS characters without spaces, so long names wrap in rendered tables. \
+                                       0 disables it, which is the default"))
+                        .arg(Arg::with_name("symbol-pass")
+                                .long("symbol-pass")
+                                .takes_value(true)
+                                .value_name("SYMBOL")
+                                .validator(validate_symbol)
+                                .help("Symbol marking passed tests in testcase breakdown"))
+                        .arg(Arg::with_name("symbol-fail")
+                                .long("symbol-fail")
+                                .takes_value(true)
+                                .value_name("SYMBOL")
+                                .validator(validate_symbol)
+                                .help("Symbol marking failed tests in testcase breakdown"))
+                        .arg(Arg::with_name("symbol-error")
+                                .long("symbol-error")
+                                .takes_value(true)
+                                .value_name("SYMBOL")
+                                .validator(validate_symbol)
+                                .help("Symbol marking errored tests in testcase breakdown"))
+                        .arg(Arg::with_name("symbol-skip")
+                                .long("symbol-skip")
+                                .takes_value(true)
+                                .value_name("SYMBOL")
+                                .validator(validate_symbol)
+                                .help("Symbol marking skipped tests in testcase breakdown"))
+                        .arg(Arg::with_name("symbol-disabled")
+                                .long("symbol-disabled")
+                                .takes_value(true)
+                                .value_name("SYMBOL")
+                                .validator(validate_symbol)
+                                .help("Symbol marking disabled tests in testcase breakdown"))
+                        .arg(Arg::with_name("label-details")
+                                .long("label-details")
+                                .takes_value(true)
+                                .value_name("LABEL")
+                                .help("Summary of collapsible section with failure details, 'Click to show details' by default"))
+                        .arg(Arg::with_name("label-stdout")
+                                .long("label-stdout")
+                                .takes_value(true)
+                                .value_name("LABEL")
+                                .help("Summary of collapsible section with test stdout, 'Click to show test stdout' by default"))
+                        .arg(Arg::with_name("label-stderr")
+                                .long("label-stderr")
+                                .takes_value(true)
+                                .value_name("LABEL")
+                                .help("Summary of collapsible section with test stderr, 'Click to show test stderr' by default"))
+                        .arg(Arg::with_name("suite-filter")
+                                .long("suite-filter")
+                                .takes_value(true)
+                                .value_name("REGEX")
+                                .validator(|value| Regex::new(&value).map(|_| ()).map_err(|err| err.to_string()))
+                                .help("Only report test suites with names matching this regular expression"))
+                        .arg(Arg::with_name("only-suite")
+                                .long("only-suite")
+                                .takes_value(true)
+                                .value_name("NAME")
+                                .multiple(true)
+                                .number_of_values(1)
+                                .help("Only report the test suite with exactly this full name, no pattern matching is done. \
+                                       Can be repeated to report several suites. Combined with --suite-filter, \
+                                       suites have to match both"))
+                        .arg(Arg::with_name("select-tests")
+                                .long("select-tests")
+                                .takes_value(true)
+                                .value_name("REGEX")
+                                .validator(|value| Regex::new(&value).map(|_| ()).map_err(|err| err.to_string()))
+                                .help("Only report test cases with names matching this regular expression, in all suites. \
+                                       Counts of suites are recomputed from the selected tests, suites without any are dropped. \
+                                       See --select-tests-by for the name that is matched"))
+                        .arg(Arg::with_name("select-tests-by")
+                                .long("select-tests-by")
+                                .takes_value(true)
+                                .value_name("NAME")
+                                .possible_values(&["full", "short"])
+                                .help("Name of test case matched by --select-tests. full by default, \
+                                       which is class name and test name joined by a dot, e.g. com.example.FooTest.bar. \
+                                       short is the test name without namespace, as shown in the testcase breakdown, see --lang"))
+                        .arg(Arg::with_name("mask-property")
+                                .long("mask-property")
+                                .takes_value(true)
+                                .value_name("REGEX")
+                                .multiple(true)
+                                .number_of_values(1)
+                                .validator(|value| Regex::new(&value).map(|_| ()).map_err(|err| err.to_string()))
+                                .help("Replace parts of suite and report property values matching this regular expression with ***, \
+                                       e.g. to hide tokens. Can be repeated"))
+                        .arg(Arg::with_name("dedupe-properties")
+                                .long("dedupe-properties")
+                                .takes_value(true)
+                                .value_name("MODE")
+                                .possible_values(&["last", "group"])
+                                .help("Merge suite and report properties declared several times with the same name. \
+                                       last keeps only the last value and warns about the dropped ones, \
+                                       group lists all values in a single entry. All declarations are listed by default"))
+                        .arg(Arg::with_name("since")
+                                .long("since")
+                                .takes_value(true)
+                                .value_name("RFC3339")
+                                .validator(|value| DateTime::parse_from_rfc3339(&value).map(|_| ()).map_err(|err| err.to_string()))
+                                .help("Only report test suites started at or after this instant, e.g. 2024-03-01T00:00:00Z. \
+                                       Suites without a valid timestamp are kept"))
+                        .arg(Arg::with_name("sort-failures")
+                                .long("sort-failures")
+                                .takes_value(true)
+                                .value_name("ORDER")
+                                .possible_values(&["input", "class", "name", "time"])
+                                .help("Order of failure details of each suite, input order by default. \
+                                       Use class to group them by class name without namespace and then by test name, \
+                                       name to order them by test name or time for slowest tests first. \
+                                       Failure references in the breakdown table follow the same numbering"))
+                        .arg(Arg::with_name("sort-suites")
+                                .long("sort-suites")
+                                .takes_value(true)
+                                .value_name("ORDER")
+                                .possible_values(&["input", "name", "time", "failures"])
+                                .help("Order of suites in aggregated report. By default suites are reported in the order \
+                                       of input files, which may differ between runs, e.g. when files come from a glob. \
+                                       Use name for reproducible reports, time for slowest suites first \
+                                       or failures for suites with most failures first"))
+                        .arg(Arg::with_name("min-severity")
+                                .long("min-severity")
+                                .takes_value(true)
+                                .value_name("SEVERITY")
+                                .possible_values(&["error", "failure", "skipped", "disabled"])
+                                .help("Only show details for tests at least this severe, skipped by default. \
+                                       Errors are more severe than failures, failures are more severe than skips, \
+                                       skips are more severe than disabled tests"))
+                        .arg(Arg::with_name("format")
+                                .long("format")
+                                .takes_value(true)
+                                .value_name("FORMAT")
+                                .possible_values(&["markdown", "term", "text", "asciidoc", "html", "json", "junit", "xunit", "testng"])
+                                .multiple(true)
+                                .number_of_values(1)
+                                .use_delimiter(true)
+                                .help("Output format, markdown by default. Use term for a short colored summary in the terminal, \
+                                       text for a plain report with aligned columns, asciidoc for an AsciiDoc document, html for an HTML fragment, \
+                                       json for a summary with counts of each suite that can be used as --baseline later. \
+                                       Colors are turned off if stdout is not a terminal or NO_COLOR is set. \
+                                       Can be repeated or given as a comma-separated list, but then --output is required. \
+                                       Input is read as JUnit XML by default, add xunit for xUnit.net v2 XML reports \
+                                       or testng for TestNG testng-results.xml"))
+                        .arg(Arg::with_name("lang")
+                                .long("lang")
+                                .takes_value(true)
+                                .value_name("LANG")
+                                .possible_values(&["java", "kotlin", "csharp"])
+                                .multiple(true)
+                                .number_of_values(1)
+                                .use_delimiter(true)
+                                .help("Language of the tested code, java by default. Namespace is removed from test and class names \
+                                       the way this language writes it: Java packages, Kotlin packages with test names in backticks, \
+                                       C# namespaces with parameters in parentheses. Can be repeated for mixed reports, \
+                                       then the first language that shortens the name is used, names that fit none are left as is"))
+                        .arg(Arg::with_name("baseline")
+                                .long("baseline")
+                                .takes_value(true)
+                                .value_name("JSON")
+                                .help("Summary of the previous run written with --format json. Counts in totals of aggregated report \
+                                       are annotated with changes since then, e.g. 5 (+2), new and removed suites are listed"))
+                        .arg(Arg::with_name("output")
+                                .long("output")
+                                .short("o")
+                                .takes_value(true)
+                                .value_name("FILE")
+                                .help("Write the report to FILE instead of stdout. If several formats are requested, \
+                                       FILE extension is replaced with the one of each format, e.g. report.md and report.txt. \
+                                       Reports written to FILE ending with .gz are compressed with gzip"))
+                        .arg(Arg::with_name("line-ending")
+                                .long("line-ending")
+                                .takes_value(true)
+                                .value_name("STYLE")
+                                .possible_values(&["lf", "crlf"])
+                                .help("Line endings of the written report, lf by default. \
+                                       Use crlf for tools on Windows that don't understand bare LF"))
+                        .arg(Arg::with_name("template")
+                                .long("template")
+                                .takes_value(true)
+                                .value_name("FILE")
+                                .help("Markdown file to put the report into, in place of {{report}} placeholder. \
+                                       Placeholders {{total}}, {{passed}}, {{failed}}, {{skipped}} and {{pass_rate}} \
+                                       are replaced with totals of the report"))
+                        .arg(Arg::with_name("footer")
+                                .long("footer")
+                                .help("Append a line noting junit2md version and number of input files to the Markdown report"))
+                        .arg(Arg::with_name("embed-source")
+                                .long("embed-source")
+                                .help("Append original content of each input file to the report, labeled with its path, \
+                                       in a collapsible block where the format has one. With --tail-output only the last lines are kept"))
+                        .arg(Arg::with_name("footer-command")
+                                .long("footer-command")
+                                .help("Include the command line the report was generated with in the footer, requires the footer. \
+                                       Only option names are shown, their values and input paths are left out"))
+                        .arg(Arg::with_name("compact")
+                                .long("compact")
+                                .help("Only print the totals table, without headers, breakdown and failure details"))
+                        .arg(Arg::with_name("failures-snippet")
+                                .long("failures-snippet")
+                                .conflicts_with_all(&["compact", "split-output", "template"])
+                                .help("Instead of Markdown report, only print a list of failed tests of all suites, \
+                                       one line each with the test name and the first line of its message, \
+                                       e.g. to paste into a chat. Skipped tests are never listed, \
+                                       --min-severity error lists only tests with errors. Respects --max-failures"))
+                        .arg(Arg::with_name("split-output")
+                                .long("split-output")
+                                .takes_value(true)
+                                .value_name("DIR")
+                                .help("Write a separate report for each suite into DIR instead of stdout, \
+                                       along with index.md linking to all of them"))
+                        .arg(Arg::with_name("split-manifest")
+                                .long("split-manifest")
+                                .takes_value(true)
+                                .value_name("FILE")
+                                .requires("split-output")
+                                .help("Also write JSON manifest of the files written with --split-output to FILE, \
+                                       e.g. DIR/manifest.json, listing path, suite name and result counts of each suite report"))
+                        .arg(Arg::with_name("exit-policy")
+                                .long("exit-policy")
+                                .takes_value(true)
+                                .value_name("POLICY")
+                                .validator(|value| ExitPolicy::parse(&value).map(|_| ()))
+                                .help("Exit with a code depending on test results, see EXIT CODES. \
+                                       POLICY is a comma-separated list of outcome=code pairs, e.g. failed=2,skipped=3, \
+                                       or 'default' to use default codes"))
+                        .arg(Arg::with_name("timing")
+                                .long("timing")
+                                .help("Print time spent reading files, parsing them and generating the report to stderr"))
+                        .arg(Arg::with_name("check")
+                                .long("check")
+                                .help("Only check that all input files can be read and parsed, don't generate the report. \
+                                       Prints a one-line summary and exits with code 1 if any file couldn't be parsed"))
+                        .arg(Arg::with_name("no-config")
+                                .long("no-config")
+                                .help("Don't read default options from the config file"))
+                        .after_help("CONFIG:\n    \
+                                     Default options can be set in .junit2md.toml file, which is looked up in the current directory \
+                                     and then in the home directory. Only the first file found is used. Keys are named as long options \
+                                     (e.g. verbose = true, bar-width = 10, header-counts = false, flavor = \"gitlab\"). \
+                                     Options given on the command line always take precedence over the config file.\n\n\
+                                     ENVIRONMENT:\n    \
+                                     JUNIT2MD_VERBOSE    Set to 1 or true to enable verbose output. Overrides the config file, \
+                                     but not the command line.\n\n\
+                                     EXIT CODES:\n    \
+                                     Exit code is 1 on fatal errors, e.g. unreadable input, or with --check if any input \
+                                     couldn't be parsed, and 0 otherwise. \
+                                     With --exit-policy it also reflects test results, the first matching outcome wins:\n        \
+                                     parse-error    some input files couldn't be parsed (3 by default)\n        \
+                                     failed         some tests failed or had errors (2 by default)\n        \
+                                     skipped        some tests were skipped or disabled (0 by default)\n        \
+                                     success        all tests passed (0 by default)")
                         .get_matches();
 
-    IS_VERBOSE.store(cli_args.is_present("verbose"), Ordering::Relaxed);
-
-    let mut junit_files = cli_args.values_of("input-files").unwrap();
-
-    // Unfortunately, serde-xml-rs doesn't fully support enum
-    // decoding (or maybe I couldn't get it to work).
-    // Once it does, the following code should be rewritten
-    // as enum JunitReport { Single(TestSuite), Multiple(TestSuiteSet) }
-
-    if junit_files.len() == 1 {
-        // it's a single file, let's try deserializing into aggregated report first
-        let junit_content = fs::read_to_string(junit_files.next().unwrap()).expect("Can't read JUnit file");
-        let mult: Result<JunitReport, XmlError> = from_reader(junit_content.as_bytes());
-        if let Some(mult) = mult.ok() {
-            if mult.testsuites.len() != 0 {
-                // that's real mult testcase, report it
-                let md = suites_to_md_mult(mult.testsuites);
-                println!("{}", md);
-                return;
-            }
-        }
+    let mut opts = load_config(cli_args.is_present("no-config"));
+    opts.apply_env_vars();
+    apply_cli_args(&mut opts, &cli_args);
+
+    if let Err(err) = opts.symbols.validate() {
+        eprintln!("Invalid status symbols: {}", err);
+        process::exit(1);
+    }
+
+    // the footer may be turned on in the config file, so this is only known once options are merged
+    if opts.footer_command && !opts.footer {
+        eprintln!("--footer-command requires the footer, turn it on with --footer");
+        process::exit(1);
+    }
+
+    if opts.format.len() > 1 && opts.output.is_none() {
+        eprintln!("Only one output format can be written to stdout, use --output to write several ones to files");
+        process::exit(1);
+    }
+
+    let mut input_files: Vec<String> = cli_args.values_of("input-files")
+                                               .map(|files| files.map(|file| file.to_owned()).collect())
+                                               .unwrap_or_default();
+    let mut timings = PhaseTimings::default();
+    if let Some(manifest) = cli_args.value_of("files-from") {
+        let read_start = Instant::now();
+        input_files.extend(read_files_manifest(manifest, &opts));
+        timings.io += read_start.elapsed();
+    }
+
+    if input_files.is_empty() {
+        eprintln!("No input files to report");
+        process::exit(1);
+    }
+
+    let junit_files: Vec<&str> = input_files.iter().map(|file| file.as_str()).collect();
+    let parse_start = Instant::now();
+    let io_before_parse = timings.io;
+    let (report, parse_errors) = match opts.input_format {
+        InputFormat::Junit => parse_junit_files(&junit_files, &opts, &mut timings),
+        InputFormat::Xunit => parse_xunit_files(&junit_files, &opts, &mut timings),
+        InputFormat::Testng => parse_testng_files(&junit_files, &opts, &mut timings),
+    };
+    // parsers account for reading files themselves, the rest is parsing
+    timings.parsing = parse_start.elapsed().saturating_sub(timings.io - io_before_parse);
+
+    if cli_args.is_present("check") {
+        check_parse_results(&report, parse_errors);
+        return;
+    }
+
+    let report = filter_suites(report, &opts);
+    let mut report = select_tests(report, &opts);
+    mask_properties(&mut report, &opts);
+    dedupe_properties(&mut report, &opts);
+    normalize_times(&mut report, &opts);
+    if opts.trust_testcases {
+        recount_testcases(&mut report);
+    }
+    if let InputReport::Aggregated(report) = &mut report {
+        sort_suites(&mut report.testsuites, &opts.sort_suites);
+    }
+    if opts.validate {
+        for suite in report.suites() {
+            validate_suite(suite, &opts);
+        }
+        validate_across_suites(report.suites(), &opts);
+    }
+
+    let totals = compute_input_totals(&report);
+    let render_start = Instant::now();
+    for format in &opts.format {
+        let output_path = opts.output.as_ref().map(|output| derive_output_path(output, *format, opts.format.len()));
+        let output = match format {
+            OutputFormat::Term => {
+                // colors only make sense when a human is looking
+                let colored = output_path.is_none() && io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+                suites_to_term(report.suites(), &totals, &opts.symbols, &opts.lang, colored)
+            }
+            OutputFormat::Text => {
+                let text = render_report(&report, TextRenderer::new(&opts), &input_files, &opts);
+                text.trim_end().to_owned() + "\n"
+            }
+            OutputFormat::Asciidoc => {
+                let adoc = render_report(&report, AsciidocRenderer::new(&opts), &input_files, &opts);
+                adoc.trim_end().to_owned() + "\n"
+            }
+            OutputFormat::Html => render_report(&report, HtmlRenderer::new(&opts), &input_files, &opts),
+            OutputFormat::Json => report_to_json(report.suites(), &totals),
+            OutputFormat::Markdown if opts.failures_snippet => failures_snippet(report.suites(), &opts),
+            OutputFormat::Markdown if opts.split_output.is_some() => {
+                write_split_output(&report, opts.split_output.as_ref().unwrap(), &opts);
+                continue;
+            }
+            OutputFormat::Markdown => {
+                let md = render_report(&report, MarkdownRenderer::new(&opts), &input_files, &opts);
+                match &opts.template {
+                    Some(template) => fill_template(template, &md, &totals, &opts),
+                    None => md + "\n",
+                }
+            }
+        };
+
+        match output_path {
+            Some(path) => write_output_file(&path, &output, &opts),
+            None => print!("{}", convert_line_endings(&output, opts.line_ending)),
+        }
+    }
+
+    timings.rendering = render_start.elapsed();
+
+    if opts.format.iter().any(|format| *format != OutputFormat::Term) && !opts.quiet {
+        // short summary for scripts, stdout is taken by the report
+        eprintln!("{} passed, {} failed, {} skipped", totals.success, totals.failed, totals.skipped + totals.disabled);
+    }
+
+    if opts.timing && !opts.quiet {
+        eprintln!("Time spent: I/O {:.3} ms, parsing {:.3} ms, rendering {:.3} ms",
+                  timings.io.as_secs_f64() * 1000.0,
+                  timings.parsing.as_secs_f64() * 1000.0,
+                  timings.rendering.as_secs_f64() * 1000.0);
+    }
+
+    if let Some(policy) = &opts.exit_policy {
+        let policy = match ExitPolicy::parse(policy) {
+            Ok(policy) => policy,
+            Err(err) => {
+                eprintln!("Invalid exit policy {}: {}", policy, err);
+                process::exit(1);
+            }
+        };
+        if opts.skips_as_failures {
+            process::exit(policy.exit_code(&totals.with_skips_as_failures(), parse_errors));
+        }
+        process::exit(policy.exit_code(&totals, parse_errors));
+    }
+}
+
+/// Returns line noting how the report was generated, added after all other sections.
+///
+/// Arguments:
+/// * `input_count` - number of input files given on the command line or in the manifest.
+/// * `opts` - report options.
+fn footer_text(input_count: usize, opts: &ReportOptions) -> String {
+    let mut footer = format!("Generated by junit2md {} from {}", env!("CARGO_PKG_VERSION"), pluralize(input_count as u64, "input file"));
+    if opts.footer_command {
+        footer.push_str(&format!(" with {}", sanitized_command_line(env::args())));
+    }
+
+    return footer;
+}
+
+/// Returns command line fit for showing in the report: program name without its directory and names of the options.
+/// Option values and input paths are left out, as they may hold tokens or other secrets.
+///
+/// Arguments:
+/// * `args` - command-line arguments, starting with the program.
+fn sanitized_command_line(mut args: impl Iterator<Item = String>) -> String {
+    let program = args.next().unwrap_or_default();
+    let program = Path::new(&program).file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or(program);
+
+    let mut command = vec![program];
+    for arg in args.filter(|arg| arg.starts_with('-')) {
+        // value given as --option=value
+        let option = arg.split('=').next().unwrap_or_default().to_owned();
+        command.push(option);
+    }
+
+    return command.join(" ");
+}
+
+/// Puts the report into Markdown template in place of `{{report}}` placeholder.
+/// Placeholders for report totals are filled in too. Unreadable template
+/// or template without `{{report}}` placeholder is a fatal error.
+///
+/// Arguments:
+/// * `template_path` - path to the template file.
+/// * `md` - generated report.
+/// * `totals` - totals of the whole report.
+/// * `opts` - report options.
+fn fill_template(template_path: &str, md: &str, totals: &ReportTotals, opts: &ReportOptions) -> String {
+    let template = match fs::read_to_string(template_path) {
+        Ok(template) => template,
+        Err(err) => {
+            eprintln!("Couldn't read template {}: {}", template_path, err);
+            process::exit(1);
+        }
+    };
+
+    if !template.contains("{{report}}") {
+        eprintln!("Template {} has no {{{{report}}}} placeholder", template_path);
+        process::exit(1);
+    }
+
+    // the report goes last, so placeholder-like text in test output stays as is
+    return template
+        .replace("{{total}}", &format_count(totals.tests))
+        .replace("{{passed}}", &format_count(totals.success))
+        .replace("{{failed}}", &format_count(totals.failed))
+        .replace("{{skipped}}", &format_count(totals.skipped + totals.disabled))
+        .replace("{{pass_rate}}", &format_pass_rate(totals, opts))
+        .replace("{{report}}", md.trim_start_matches('\n'));
+}
+
+/// Prints outcome of `--check` run and exits with code 1 if any input couldn't be parsed.
+///
+/// Arguments:
+/// * `report` - suites that were parsed successfully.
+/// * `parse_errors` - number of inputs that couldn't be parsed, each of them was already reported.
+fn check_parse_results(report: &InputReport, parse_errors: usize) {
+    let suites = report.suites();
+    let tests: u64 = suites.iter().map(|suite| suite.tests).sum();
+    let parsed = format!("{} with {}", pluralize(suites.len() as u64, "suite"), pluralize(tests, "test"));
+
+    if parse_errors > 0 {
+        eprintln!("Check failed: {} couldn't be parsed, {} parsed", pluralize(parse_errors as u64, "report"), parsed);
+        process::exit(1);
+    }
+
+    println!("OK: {} parsed", parsed);
+}
+
+/// Writes each suite of the report into its own Markdown file in the output directory,
+/// plus `index.md` linking to all of them with their totals.
+/// Files are named after suites, see `suite_file_name`.
+/// Failure to write any file is fatal, so this exits the process on failure.
+///
+/// Arguments:
+/// * `report` - parsed report to write.
+/// * `output_dir` - directory to write files to, created if missing.
+/// * `opts` - report options.
+fn write_split_output(report: &InputReport, output_dir: &str, opts: &ReportOptions) {
+
+    let output_dir = Path::new(output_dir);
+    if let Err(err) = fs::create_dir_all(output_dir) {
+        eprintln!("Can't create output directory {}: {}", output_dir.display(), err);
+        process::exit(1);
+    }
+
+    let mut index = String::new();
+    create_h1(&mut index, &escape_inline(&report_title(opts, "Test report index")));
+
+    let mut table = vec![vec![
+        "Suite name".to_owned(),
+        "Success".to_owned(),
+        "Skipped".to_owned(),
+        "Failures".to_owned(),
+        "Total".to_owned(),
+    ]];
+
+    let mut used_names = BTreeSet::new();
+    let mut suite_files = vec![];
+    for suite in report.suites() {
+        // suites with the same name must not overwrite each other
+        let base_name = suite_file_name(suite.display_name());
+        let mut file_name = format!("{}.md", base_name);
+        let mut duplicate_index = 1;
+        while used_names.contains(&file_name) || file_name == "index.md" {
+            duplicate_index += 1;
+            file_name = format!("{}-{}.md", base_name, duplicate_index);
+        }
+
+        let totals = compute_suite_totals(suite);
+        table.push(vec![
+            format!("[{}]({})", escape_inline(suite.short_name(&opts.lang)), file_name),
+            format_count(totals.success),
+            format_count(totals.skipped + totals.disabled),
+            format_count(totals.failed),
+            format_count(totals.tests),
+        ]);
+
+        let mut renderer = MarkdownRenderer::new(opts);
+        render_suite(suite, &mut renderer, opts);
+        let suite_file = output_dir.join(&file_name);
+        write_output_file(&suite_file, &renderer.finish(), opts);
+        suite_files.push((suite_file.display().to_string(), suite));
+        used_names.insert(file_name);
+    }
+
+    create_md_table_with_width(&mut index, table, true, opts.table_width);
+    let index_file = output_dir.join("index.md");
+    write_output_file(&index_file, &index, opts);
+
+    if let Some(manifest_file) = &opts.split_manifest {
+        let manifest = split_manifest_to_json(&index_file.display().to_string(), &suite_files);
+        write_output_file(Path::new(manifest_file), &manifest, opts);
+    }
+}
+
+/// Derives file name from the suite name, replacing everything except
+/// ASCII letters, digits, dots, dashes and underscores with underscores.
+///
+/// Arguments:
+/// * `suite_name` - name of the suite.
+fn suite_file_name(suite_name: &str) -> String {
+    let file_name: String = suite_name.trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+
+    let file_name = file_name.trim_start_matches('.');
+    if file_name.is_empty() {
+        return "suite".to_owned();
+    }
+
+    return file_name.to_owned();
+}
+
+/// Writes report file with requested line endings, failure to do so is fatal.
+/// Files with `.gz` extension are compressed with gzip.
+///
+/// Arguments:
+/// * `path` - path of the file to write.
+/// * `content` - report content.
+/// * `opts` - report options.
+fn write_output_file(path: &Path, content: &str, opts: &ReportOptions) {
+    let content = convert_line_endings(content, opts.line_ending);
+    let written = if is_gzip_file(path) {
+        fs::File::create(path).and_then(|file| {
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            encoder.write_all(content.as_bytes())?;
+            return encoder.finish().map(|_| ());
+        })
+    } else {
+        fs::write(path, content)
+    };
+
+    if let Err(err) = written {
+        eprintln!("Can't write report file {}: {}", path.display(), err);
+        process::exit(1);
+    }
+}
+
+/// Converts line endings of the rendered report, which is always `\n`-based, to the requested style.
+/// Stray `\r\n` from test output is normalized first, so it doesn't become `\r\r\n`.
+///
+/// Arguments:
+/// * `content` - rendered report.
+/// * `line_ending` - line endings to convert to.
+fn convert_line_endings(content: &str, line_ending: LineEnding) -> String {
+    match line_ending {
+        LineEnding::Lf => return content.to_owned(),
+        LineEnding::Crlf => return content.replace("\r\n", "\n").replace('\n', "\r\n"),
+    }
+}
+
+/// Time spent in each phase of the conversion, reported with `--timing`
+#[derive(Default)]
+struct PhaseTimings {
+    /// Reading input files
+    io: Duration,
+    /// Deserializing XML into test suites
+    parsing: Duration,
+    /// Generating the report and writing it out
+    rendering: Duration,
+}
+
+/// Ids of anchors already used in one document.
+/// Every anchor goes through it, so links within the document always lead to a single target.
+#[derive(Default)]
+struct DocumentAnchors<'a> {
+    /// Tests with failure details, in the order they are reported, see `ordered_failures`
+    failures: Vec<&'a TestCase>,
+    /// Ids of testcase row anchors
+    rows: BTreeSet<String>,
+}
+
+impl<'a> DocumentAnchors<'a> {
+    /// Creates anchors of the document with failure details of the given tests.
+    fn new(failures: Vec<&'a TestCase>) -> Self {
+        return DocumentAnchors { failures, rows: BTreeSet::new() };
+    }
+
+    /// Returns index of the failure details anchor of the test, see `failure_anchor`.
+    /// Failures are numbered across the whole document in the order they are reported.
+    fn failure(&self, test: &TestCase) -> Option<usize> {
+        return self.failures.iter().position(|failed| ptr::eq(*failed, test));
+    }
+
+    /// Returns id of the anchor for testcase row, tests with the same name get numbered ones.
+    ///
+    /// Arguments:
+    /// * `test_name` - name of the test in the row.
+    fn next_row(&mut self, test_name: &str) -> String {
+        let base_slug = format!("test-{}", create_slug(test_name));
+        let mut slug = base_slug.clone();
+        let mut duplicate_index = 1;
+        while !self.rows.insert(slug.clone()) {
+            duplicate_index += 1;
+            slug = format!("{}-{}", base_slug, duplicate_index);
+        }
+        return slug;
+    }
+}
+
+/// Returns id of the anchor for failure details with the given document-wide index.
+fn failure_anchor(fail_index: usize) -> String {
+    return format!("c-{}", fail_index);
+}
+
+/// Returns id of the anchor for failures of the suite with the given index in the aggregated report.
+fn suite_anchor(suite_index: usize) -> String {
+    return format!("suite-{}", suite_index);
+}
+
+/// Derives path of the report file for the output format.
+/// If there's only one format, the path is used as is,
+/// otherwise its extension is replaced with the one of the format.
+/// Compressed outputs keep their `.gz` suffix, e.g. `report.md.gz` becomes `report.txt.gz`.
+///
+/// Arguments:
+/// * `output` - output path given by user.
+/// * `format` - format of the report to write.
+/// * `format_count` - number of formats written in this run.
+fn derive_output_path(output: &str, format: OutputFormat, format_count: usize) -> PathBuf {
+    let path = PathBuf::from(output);
+    if format_count == 1 {
+        return path;
+    }
+
+    if is_gzip_file(&path) {
+        return path.with_extension("").with_extension(format!("{}.gz", format.extension()));
+    }
+
+    return path.with_extension(format.extension());
+}
+
+/// Reads list of input files from the manifest file.
+/// Manifest contains one path per line, blank lines and `#` comments are skipped.
+/// Listed files that don't exist are reported and skipped.
+/// Unreadable manifest is a fatal error, so this exits the process on failure.
+///
+/// Arguments:
+/// * `manifest` - path to the manifest file, `-` for stdin.
+/// * `opts` - report options.
+fn read_files_manifest(manifest: &str, opts: &ReportOptions) -> Vec<String> {
+    let content = match manifest {
+        "-" => io::read_to_string(io::stdin()),
+        _ => fs::read_to_string(manifest),
+    };
+
+    let content = match content {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("Can't read file list {}: {}", manifest, err);
+            process::exit(1);
+        }
+    };
+
+    let mut files = vec![];
+    for (line_idx, line) in content.lines().enumerate() {
+        let path = line.trim();
+        if path.is_empty() || path.starts_with('#') {
+            // blank line or comment
+            continue;
+        }
+
+        if !Path::new(path).is_file() {
+            warn(opts, &format!("Can't read JUnit file {} listed in {} at line {}: file not found", path, manifest, line_idx + 1));
+            continue;
+        }
+
+        files.push(path.to_owned());
+    }
+    return files;
+}
+
+/// Checks that status symbol given on the command line is not empty.
+fn validate_symbol(value: String) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err("symbol must not be empty".to_owned());
+    }
+
+    return Ok(());
+}
+
+/// Loads default report options from the config file, if there is one.
+/// Malformed config file is a fatal error, so this exits the process on failure.
+///
+/// Arguments:
+/// * `skip_config` - don't look for config file, just return defaults.
+fn load_config(skip_config: bool) -> ReportOptions {
+    if skip_config {
+        return ReportOptions::default();
+    }
+
+    let config_path = match find_config_file() {
+        Some(path) => path,
+        None => return ReportOptions::default(),
+    };
+
+    match ReportOptions::from_config_file(&config_path) {
+        Ok(opts) => return opts,
+        Err(err) => {
+            eprintln!("Can't read config file {}: {}", config_path.display(), err);
+            process::exit(1);
+        }
+    }
+}
+
+/// Overrides report options with ones given on the command line.
+///
+/// Arguments:
+/// * `opts` - report options, possibly loaded from config file.
+/// * `cli_args` - parsed command-line arguments.
+fn apply_cli_args(opts: &mut ReportOptions, cli_args: &ArgMatches) {
+    if cli_args.is_present("verbose") {
+        opts.verbose = true;
+    }
+
+    if cli_args.is_present("relative-time") {
+        opts.relative_time = true;
+    }
+
+    if cli_args.is_present("exclude-skipped-from-rate") {
+        opts.exclude_skipped_from_rate = true;
+    }
+
+    if cli_args.is_present("skips-as-failures") {
+        opts.skips_as_failures = true;
+    }
+
+    if cli_args.is_present("show-timestamps") {
+        opts.show_timestamps = true;
+    }
+
+    if cli_args.is_present("verbose-passing") {
+        opts.verbose_passing = true;
+    }
+
+    if cli_args.is_present("quiet") {
+        opts.quiet = true;
+    }
+
+    if let Some(width) = cli_args.value_of("bar-width") {
+        opts.bar_width = width.parse().unwrap();
+    }
+
+    if let Some(columns) = cli_args.value_of("columns") {
+        opts.columns = Some(TotalsColumn::parse_list(columns).unwrap());
+    }
+
+    if let Some(width) = cli_args.value_of("width") {
+        opts.table_width = Some(width.parse().unwrap());
+    }
+
+    if let Some(title) = cli_args.value_of("title") {
+        opts.title = Some(title.to_owned());
+    }
+
+    if let Some(title_file) = cli_args.value_of("report-title-from-file") {
+        opts.file_title = Some(read_title_file(title_file));
+    }
+
+    if cli_args.is_present("no-header-counts") {
+        opts.header_counts = false;
+    }
+
+    match cli_args.value_of("flavor") {
+        Some("github") => opts.flavor = Flavor::Github,
+        Some("gitlab") => opts.flavor = Flavor::Gitlab,
+        _ => {}
+    }
+
+    if cli_args.is_present("no-collapse") {
+        opts.collapse = false;
+    }
+
+    if cli_args.is_present("no-details") {
+        opts.details = false;
+    }
+
+    if cli_args.is_present("no-percent") {
+        opts.percent = false;
+    }
+
+    if cli_args.is_present("validate") {
+        opts.validate = true;
+    }
+
+    if cli_args.is_present("show-reported-status") {
+        opts.show_reported_status = true;
+    }
+
+    if cli_args.is_present("show-retries") {
+        opts.show_retries = true;
+    }
+
+    if cli_args.is_present("toc") {
+        opts.toc = true;
+    }
+
+    if cli_args.is_present("health-badge") {
+        opts.health_badge = true;
+    }
+
+    if cli_args.is_present("no-summary-line") {
+        opts.summary_line = false;
+    }
+
+    if cli_args.is_present("suite-status") {
+        opts.suite_status = true;
+    }
+
+    if cli_args.is_present("sparkline") {
+        opts.sparkline = true;
+    }
+
+    if cli_args.is_present("row-anchors") {
+        opts.row_anchors = true;
+    }
+
+    if cli_args.is_present("show-classname") {
+        opts.show_classname = true;
+    }
+
+    if cli_args.is_present("cause-type") {
+        opts.cause_type = true;
+    }
+
+    if cli_args.is_present("strip-ansi") {
+        opts.strip_ansi = true;
+    }
+
+    if let Some(lines) = cli_args.value_of("tail-output") {
+        opts.tail_output = Some(lines.parse().unwrap());
+    }
+
+    if let Some(count) = cli_args.value_of("max-failures") {
+        opts.max_failures = Some(count.parse().unwrap());
+    }
+
+    if cli_args.is_present("list-skips") {
+        opts.list_skips = true;
+    }
+
+    if cli_args.is_present("show-flaky") {
+        opts.show_flaky = true;
+    }
+
+    if cli_args.is_present("by-class") {
+        opts.by_class = true;
+    }
+
+    if let Some(width) = cli_args.value_of("soft-break-width") {
+        opts.soft_break_width = width.parse().unwrap();
+    }
+
+    if let Some(symbol) = cli_args.value_of("symbol-pass") {
+        opts.symbols.pass = symbol.to_owned();
+    }
+
+    if let Some(symbol) = cli_args.value_of("symbol-fail") {
+        opts.symbols.fail = symbol.to_owned();
+    }
+
+    if let Some(symbol) = cli_args.value_of("symbol-error") {
+        opts.symbols.error = symbol.to_owned();
+    }
+
+    if let Some(symbol) = cli_args.value_of("symbol-skip") {
+        opts.symbols.skip = symbol.to_owned();
+    }
+
+    if let Some(symbol) = cli_args.value_of("symbol-disabled") {
+        opts.symbols.disabled = symbol.to_owned();
+    }
+
+    if let Some(label) = cli_args.value_of("label-details") {
+        opts.labels.details = label.to_owned();
+    }
+
+    if let Some(label) = cli_args.value_of("label-stdout") {
+        opts.labels.stdout = label.to_owned();
+    }
+
+    if let Some(label) = cli_args.value_of("label-stderr") {
+        opts.labels.stderr = label.to_owned();
+    }
+
+    if let Some(pattern) = cli_args.value_of("suite-filter") {
+        opts.suite_filter = Some(pattern.to_owned());
+    }
+
+    if let Some(names) = cli_args.values_of("only-suite") {
+        opts.only_suite = names.map(str::to_owned).collect();
+    }
+
+    if let Some(patterns) = cli_args.values_of("mask-property") {
+        // masks from the config file still apply, better hide too much than leak a secret
+        opts.mask_property.extend(patterns.map(str::to_owned));
+    }
+
+    match cli_args.value_of("dedupe-properties") {
+        Some("last") => opts.dedupe_properties = Some(PropertyDedupe::Last),
+        Some("group") => opts.dedupe_properties = Some(PropertyDedupe::Group),
+        _ => {}
+    }
+
+    if let Some(pattern) = cli_args.value_of("select-tests") {
+        opts.select_tests = Some(pattern.to_owned());
+    }
+
+    match cli_args.value_of("select-tests-by") {
+        Some("full") => opts.select_tests_by = TestNameMatch::Full,
+        Some("short") => opts.select_tests_by = TestNameMatch::Short,
+        _ => {}
+    }
+
+    if let Some(since) = cli_args.value_of("since") {
+        opts.since = Some(since.to_owned());
+    }
+
+    match cli_args.value_of("sort-failures") {
+        Some("input") => opts.sort_failures = FailureOrder::Input,
+        Some("class") => opts.sort_failures = FailureOrder::Class,
+        Some("name") => opts.sort_failures = FailureOrder::Name,
+        Some("time") => opts.sort_failures = FailureOrder::Time,
+        _ => {}
+    }
+
+    match cli_args.value_of("sort-suites") {
+        Some("input") => opts.sort_suites = SuiteOrder::Input,
+        Some("name") => opts.sort_suites = SuiteOrder::Name,
+        Some("time") => opts.sort_suites = SuiteOrder::Time,
+        Some("failures") => opts.sort_suites = SuiteOrder::Failures,
+        _ => {}
+    }
+
+    match cli_args.value_of("min-severity") {
+        Some("error") => opts.min_severity = TestOutcome::Error,
+        Some("failure") => opts.min_severity = TestOutcome::Failure,
+        Some("skipped") => opts.min_severity = TestOutcome::Skipped,
+        Some("disabled") => opts.min_severity = TestOutcome::Disabled,
+        _ => {}
+    }
+
+    if let Some(template) = cli_args.value_of("template") {
+        opts.template = Some(template.to_owned());
+    }
+
+    if cli_args.is_present("footer") {
+        opts.footer = true;
+    }
+
+    if cli_args.is_present("footer-command") {
+        opts.footer_command = true;
+    }
+
+    if cli_args.is_present("embed-source") {
+        opts.embed_source = true;
+    }
+
+    if cli_args.is_present("failures-snippet") {
+        opts.failures_snippet = true;
+    }
+
+    if cli_args.is_present("compact") {
+        opts.compact = true;
+    }
+
+    if let Some(output_dir) = cli_args.value_of("split-output") {
+        opts.split_output = Some(output_dir.to_owned());
+    }
+
+    if let Some(manifest_file) = cli_args.value_of("split-manifest") {
+        opts.split_manifest = Some(manifest_file.to_owned());
+    }
+
+    if let Some(policy) = cli_args.value_of("exit-policy") {
+        opts.exit_policy = Some(policy.to_owned());
+    }
+
+    if cli_args.is_present("trust-testcases") {
+        opts.trust_testcases = true;
+    }
+
+    if cli_args.is_present("timing") {
+        opts.timing = true;
+    }
+
+    if let Some(formats) = cli_args.values_of("format") {
+        let mut output_formats = vec![];
+        for format in formats {
+            let format = match format {
+                // input formats are selected with the same option, e.g. --format xunit
+                "junit" => {
+                    opts.input_format = InputFormat::Junit;
+                    continue;
+                }
+                "xunit" => {
+                    opts.input_format = InputFormat::Xunit;
+                    continue;
+                }
+                "testng" => {
+                    opts.input_format = InputFormat::Testng;
+                    continue;
+                }
+                "term" => OutputFormat::Term,
+                "text" => OutputFormat::Text,
+                "asciidoc" => OutputFormat::Asciidoc,
+                "html" => OutputFormat::Html,
+                "json" => OutputFormat::Json,
+                _ => OutputFormat::Markdown,
+            };
+            if !output_formats.contains(&format) {
+                output_formats.push(format);
+            }
+        }
+        // only input format given, output format stays as configured
+        if !output_formats.is_empty() {
+            opts.format = output_formats;
+        }
+    }
+
+    if let Some(langs) = cli_args.values_of("lang") {
+        opts.lang.clear();
+        for lang in langs {
+            let lang = match lang {
+                "kotlin" => Lang::Kotlin,
+                "csharp" => Lang::Csharp,
+                _ => Lang::Java,
+            };
+            if !opts.lang.contains(&lang) {
+                opts.lang.push(lang);
+            }
+        }
+    }
+
+    if let Some(output) = cli_args.value_of("output") {
+        opts.output = Some(output.to_owned());
+    }
+
+    if let Some(baseline) = cli_args.value_of("baseline") {
+        opts.baseline = Some(read_baseline(baseline));
+    }
+
+    match cli_args.value_of("line-ending") {
+        Some("lf") => opts.line_ending = LineEnding::Lf,
+        Some("crlf") => opts.line_ending = LineEnding::Crlf,
+        _ => {}
+    }
+}
+
+/// Drops test suites whose names don't match the suite filter or aren't selected with `--only-suite`,
+/// or which were started before the `--since` instant from the report. Suites without parseable timestamp are kept.
+/// Selected names that no suite has are reported, they're likely typos.
+/// Totals declared on the aggregated report root are dropped too if any suite was filtered out,
+/// as they don't describe the remaining suites anymore.
+/// Single suite that doesn't match turns into an empty aggregated report.
+///
+/// Arguments:
+/// * `report` - parsed report to filter.
+/// * `opts` - report options with the filters.
+fn filter_suites(report: InputReport, opts: &ReportOptions) -> InputReport {
+    if opts.suite_filter.is_none() && opts.only_suite.is_empty() && opts.since.is_none() {
+        return report;
+    }
+
+    for name in &opts.only_suite {
+        if !report.suites().iter().any(|suite| suite.display_name() == name) {
+            warn(opts, &format!("No test suite named {} to report", name));
+        }
+    }
+
+    let suite_filter = opts.suite_filter.as_ref().map(|pattern| match Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(err) => {
+            eprintln!("Invalid suite filter {}: {}", pattern, err);
+            process::exit(1);
+        }
+    });
+
+    let since = opts.since.as_ref().map(|since| match DateTime::parse_from_rfc3339(since) {
+        Ok(since) => since.with_timezone(&Utc),
+        Err(err) => {
+            eprintln!("Invalid --since instant {}: {}", since, err);
+            process::exit(1);
+        }
+    });
+
+    let is_reported = |suite: &TestSuite| {
+        if let Some(suite_filter) = &suite_filter {
+            if !suite_filter.is_match(suite.display_name()) {
+                return false;
+            }
+        }
+
+        if !opts.only_suite.is_empty() && !opts.only_suite.iter().any(|name| name == suite.display_name()) {
+            return false;
+        }
+
+        if let Some(since) = since {
+            match suite.timestamp.as_deref().and_then(parse_timestamp) {
+                Some(timestamp) => return timestamp >= since,
+                None => {
+                    // better report too much than lose results
+                    warn(opts, &format!("Suite {} has no valid timestamp, keeping it despite --since", suite.display_name()));
+                }
+            }
+        }
+
+        return true;
+    };
+
+    match report {
+        InputReport::Single(suite) if is_reported(&suite) => return InputReport::Single(suite),
+        InputReport::Single(_) => return InputReport::Aggregated(JunitReport::default()),
+        InputReport::Aggregated(mut report) => {
+            let suite_count = report.testsuites.len();
+            report.testsuites.retain(is_reported);
+            if report.testsuites.len() != suite_count {
+                // declared totals are for the whole report, not for the filtered one
+                report = JunitReport { testsuites: report.testsuites, properties: report.properties, ..Default::default() };
+            }
+            return InputReport::Aggregated(report);
+        }
+    }
+}
+
+/// Keeps only test cases with names matching `--select-tests`, along with suites that still have some.
+/// Counts and times of suites with removed tests are recomputed from the remaining ones,
+/// declared totals of the aggregated report are dropped as they don't describe the selection.
+/// Single suite without any matching tests turns into an empty aggregated report.
+/// Invalid expression is a fatal error.
+///
+/// Arguments:
+/// * `report` - parsed report to select tests from.
+/// * `opts` - report options with the expression.
+fn select_tests(report: InputReport, opts: &ReportOptions) -> InputReport {
+    let pattern = match &opts.select_tests {
+        Some(pattern) => pattern,
+        None => return report,
+    };
+
+    let regex = match Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(err) => {
+            eprintln!("Invalid test selection {}: {}", pattern, err);
+            process::exit(1);
+        }
+    };
+
+    let is_selected = |test: &TestCase| match opts.select_tests_by {
+        TestNameMatch::Full => match test.classname.as_deref() {
+            Some(classname) if !classname.is_empty() => return regex.is_match(&format!("{}.{}", classname, test.name)),
+            _ => return regex.is_match(&test.name),
+        },
+        TestNameMatch::Short => return regex.is_match(strip_namespace(&test.name, &opts.lang)),
+    };
+
+    let select_in_suite = |suite: &mut TestSuite| {
+        let test_count = suite.testcases.len();
+        suite.testcases.retain(is_selected);
+        if suite.testcases.len() != test_count {
+            // declared counts and time are for all tests of the suite
+            recount_suite(suite);
+            suite.time = sum_times(suite.testcases.iter().map(|test| &test.time));
+        }
+        return !suite.testcases.is_empty();
+    };
+
+    match report {
+        InputReport::Single(mut suite) => {
+            if select_in_suite(&mut suite) {
+                return InputReport::Single(suite);
+            }
+            return InputReport::Aggregated(JunitReport::default());
+        }
+        InputReport::Aggregated(mut report) => {
+            report.testsuites.retain_mut(select_in_suite);
+            return InputReport::Aggregated(JunitReport { testsuites: report.testsuites, properties: report.properties, ..Default::default() });
+        }
+    }
+}
+
+/// Replaces parts of suite and report property values matching any of `--mask-property` expressions with `***`.
+/// Invalid expression is a fatal error.
+///
+/// Arguments:
+/// * `report` - parsed report to mask properties in.
+/// * `opts` - report options with expressions to mask.
+fn mask_properties(report: &mut InputReport, opts: &ReportOptions) {
+    if opts.mask_property.is_empty() {
+        return;
+    }
+
+    let masks: Vec<Regex> = opts.mask_property.iter().map(|pattern| match Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(err) => {
+            eprintln!("Invalid property mask {}: {}", pattern, err);
+            process::exit(1);
+        }
+    }).collect();
+
+    let mask_values = |properties: &mut Option<TestProperties>| {
+        let properties = properties.iter_mut().flat_map(|properties| properties.properties.iter_mut());
+        for value in properties.filter_map(|prop| prop.value.as_mut()) {
+            for mask in &masks {
+                *value = mask.replace_all(value, "***").into_owned();
+            }
+        }
+    };
+
+    for suite in report.suites_mut() {
+        mask_values(&mut suite.properties);
+    }
+    if let InputReport::Aggregated(report) = report {
+        mask_values(&mut report.properties);
+    }
+}
+
+/// Merges properties declared several times with the same name in each suite and in the whole report,
+/// see `--dedupe-properties`. Merged property takes the place of the first declaration.
+///
+/// Arguments:
+/// * `report` - parsed report to merge properties in.
+/// * `opts` - report options.
+fn dedupe_properties(report: &mut InputReport, opts: &ReportOptions) {
+    let mode = match &opts.dedupe_properties {
+        Some(mode) => mode,
+        None => return,
+    };
+
+    for suite in report.suites_mut() {
+        let owner = format!("Suite {}", suite.display_name());
+        dedupe_property_list(&mut suite.properties, mode, &owner, opts);
+    }
+    if let InputReport::Aggregated(report) = report {
+        dedupe_property_list(&mut report.properties, mode, "Report", opts);
+    }
+}
+
+/// Merges properties with the same name in a single properties list.
+///
+/// Arguments:
+/// * `properties` - properties of a suite or the whole report.
+/// * `mode` - how to merge values of the same property.
+/// * `owner` - suite or report the properties belong to, for warnings.
+/// * `opts` - report options.
+fn dedupe_property_list(properties: &mut Option<TestProperties>, mode: &PropertyDedupe, owner: &str, opts: &ReportOptions) {
+    let properties = match properties {
+        Some(properties) => &mut properties.properties,
+        None => return,
+    };
+
+    let mut declared: Vec<(String, Vec<Option<String>>)> = vec![];
+    for prop in properties.drain(..) {
+        match declared.iter_mut().find(|(name, _)| *name == prop.name) {
+            Some((_, values)) => values.push(prop.value),
+            None => declared.push((prop.name, vec![prop.value])),
+        }
+    }
+
+    for (name, mut values) in declared {
+        let value = match mode {
+            PropertyDedupe::Last => {
+                if values.len() > 1 {
+                    warn(opts, &format!("{}: property {} is declared {} times, keeping the last value", owner, name, values.len()));
+                }
+                values.pop().flatten()
+            }
+            PropertyDedupe::Group => join_property_values(values),
+        };
+        properties.push(TestProperty { name, value });
+    }
+}
+
+/// Joins values of the same property, skipping missing ones.
+/// Multi-line values are separated by line breaks, so each of them still starts on its own line.
+fn join_property_values(values: Vec<Option<String>>) -> Option<String> {
+    let values: Vec<String> = values.into_iter().flatten().collect();
+    if values.is_empty() {
+        return None;
+    }
+
+    let separator = if values.iter().any(|value| value.contains('\n')) { "\n" } else { ", " };
+    return Some(values.join(separator));
+}
+
+/// Normalizes times of the report, its suites and tests, so they can be parsed as numbers downstream.
+/// See `normalize_time`.
+///
+/// Arguments:
+/// * `report` - parsed report to normalize times in.
+/// * `opts` - report options.
+fn normalize_times(report: &mut InputReport, opts: &ReportOptions) {
+    if let InputReport::Aggregated(report) = report {
+        normalize_time(&mut report.time, || "Report".to_owned(), opts);
+    }
+
+    for suite in report.suites_mut() {
+        let suite_name = suite.display_name().to_owned();
+        normalize_time(&mut suite.time, || format!("Suite {}", suite_name), opts);
+        for test in &mut suite.testcases {
+            let test_name = &test.name;
+            normalize_time(&mut test.time, || format!("Suite {}: test case {}", suite_name, test_name), opts);
+        }
+    }
+}
+
+/// Replaces declared counts of suites with counts of outcomes of their test cases, see `--trust-testcases`.
+/// Declared counts of the whole report are dropped, so they are summed from suites.
+/// Missing times are summed from test cases and suites.
+///
+/// Arguments:
+/// * `report` - parsed report to recount.
+fn recount_testcases(report: &mut InputReport) {
+    for suite in report.suites_mut() {
+        recount_suite(suite);
+        if suite.time.is_none() {
+            suite.time = sum_times(suite.testcases.iter().map(|test| &test.time));
+        }
+    }
+
+    if let InputReport::Aggregated(report) = report {
+        report.tests = None;
+        report.failures = None;
+        report.errors = None;
+        report.skipped = None;
+        report.disabled = None;
+        if report.time.is_none() {
+            report.time = sum_times(report.testsuites.iter().map(|suite| &suite.time));
+        }
+    }
+}
+
+/// Replaces declared counts of the suite with counts of outcomes of its test cases.
+fn recount_suite(suite: &mut TestSuite) {
+    let count = |outcome: TestOutcome| suite.testcases.iter().filter(|test| test.outcome() == outcome).count() as u64;
+    let (failures, errors) = (count(TestOutcome::Failure), count(TestOutcome::Error));
+    let (skipped, disabled) = (count(TestOutcome::Skipped), count(TestOutcome::Disabled));
+
+    suite.tests = suite.testcases.len() as u64;
+    suite.failures = Some(failures);
+    suite.errors = Some(errors);
+    suite.skipped = Some(skipped);
+    suite.disabled = Some(disabled);
+}
+
+/// Sums times in seconds, formatted with millisecond precision.
+/// Returns `None` if there are no times, or some of them are missing or not numbers.
+fn sum_times<'a>(times: impl Iterator<Item = &'a Option<String>>) -> Option<String> {
+    let mut total = 0.0;
+    let mut count = 0;
+    for time in times {
+        total += time.as_deref()?.trim().parse::<f64>().ok()?;
+        count += 1;
+    }
+
+    match count {
+        0 => return None,
+        _ => return Some(format!("{:.3}", total)),
+    }
+}
+
+/// Normalizes time in seconds: thousands separators are dropped, e.g. `1,234.5` becomes `1234.5`,
+/// and negative times become `0` with a warning. Times that aren't numbers at all are left as is.
+///
+/// Arguments:
+/// * `time` - time to normalize.
+/// * `owner` - describes what the time belongs to, for the warning.
+/// * `opts` - report options.
+fn normalize_time(time: &mut Option<String>, owner: impl Fn() -> String, opts: &ReportOptions) {
+    let raw = match time {
+        Some(raw) => raw.trim(),
+        None => return,
+    };
+
+    let number = strip_thousands_separators(raw);
+    match number.as_deref().unwrap_or(raw).parse::<f64>() {
+        Ok(seconds) if seconds.is_sign_negative() => {
+            warn(opts, &format!("{} has negative time {}, it's shown as 0", owner(), raw));
+            *time = Some("0".to_owned());
+        }
+        Ok(_) if number.is_some() => *time = number,
+        // either fine already or can't be interpreted, then shown as is
+        _ => {}
+    }
+}
+
+/// Removes commas separating thousands from a number, e.g. `-12,345.6` becomes `-12345.6`.
+/// Returns `None` if the text has no commas or they're not thousands separators.
+///
+/// Arguments:
+/// * `text` - number to remove separators from.
+fn strip_thousands_separators(text: &str) -> Option<String> {
+    let digits = text.trim_start_matches(['+', '-']);
+    let integer_part = digits.split('.').next().unwrap_or_default();
+    let mut groups = integer_part.split(',');
+    let first_group = groups.next().unwrap_or_default();
+    let all_digits = |group: &str| !group.is_empty() && group.chars().all(|c| c.is_ascii_digit());
+
+    if !integer_part.contains(',') || first_group.len() > 3 || !all_digits(first_group)
+        || !groups.all(|group| group.len() == 3 && all_digits(group)) {
+        return None;
+    }
+
+    return Some(text.replacen(',', "", integer_part.matches(',').count()));
+}
+
+/// Sorts suites of aggregated report in the requested order, see `SuiteOrder`.
+///
+/// Arguments:
+/// * `suites` - suites to sort.
+/// * `order` - order to sort them in.
+fn sort_suites(suites: &mut [TestSuite], order: &SuiteOrder) {
+    let suite_time = |suite: &TestSuite| suite.time.as_deref().and_then(|time| time.trim().parse::<f64>().ok()).unwrap_or(0.0);
+    let failed_tests = |suite: &TestSuite| compute_suite_totals(suite).failed;
+
+    match order {
+        SuiteOrder::Input => return,
+        SuiteOrder::Name => suites.sort_by(|a, b| a.display_name().cmp(b.display_name())),
+        SuiteOrder::Time => suites.sort_by(|a, b| {
+            suite_time(b).total_cmp(&suite_time(a)).then_with(|| a.display_name().cmp(b.display_name()))
+        }),
+        SuiteOrder::Failures => suites.sort_by(|a, b| {
+            failed_tests(b).cmp(&failed_tests(a)).then_with(|| a.display_name().cmp(b.display_name()))
+        }),
+    }
+}
+
+/// Checks whether the report file should be gzipped, judging by its extension.
+fn is_gzip_file(path: &Path) -> bool {
+    return path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gz"));
+}
+
+/// Prints a non-fatal warning to stderr, unless quiet mode is on.
+///
+/// Arguments:
+/// * `opts` - report options to check quiet flag in.
+/// * `message` - warning text to print.
+fn warn(opts: &ReportOptions, message: &str) {
+    if opts.quiet {
+        return;
+    }
+
+    eprintln!("{}", message);
+}
+
+/// Renders the whole report: just the totals table in compact mode,
+/// detailed report of a single suite or brief report of several ones otherwise.
+///
+/// Arguments:
+/// * `report` - parsed report to render.
+/// * `renderer` - renderer of the output format.
+/// * `input_count` - number of input files, noted in the footer.
+/// * `opts` - report options.
+fn render_report<R: ReportRenderer>(report: &InputReport, mut renderer: R, input_files: &[String], opts: &ReportOptions) -> String {
+    match report {
+        _ if opts.compact => render_totals(report, &mut renderer),
+        InputReport::Single(suite) => render_suite(suite, &mut renderer, opts),
+        InputReport::Aggregated(report) => render_suites(report, &mut renderer, opts),
+    }
+
+    if opts.embed_source && !opts.compact {
+        render_input_sources(&mut renderer, input_files, opts);
+    }
+    if opts.footer {
+        renderer.footer(&footer_text(input_files.len(), opts));
+    }
+
+    return renderer.finish();
+}
+
+/// Renders section with original content of input files, see `--embed-source`.
+/// Files that can't be read as text, such as ZIP archives, are left out with a warning.
+///
+/// Arguments:
+/// * `renderer` - renderer of the output format.
+/// * `input_files` - paths of input files.
+/// * `opts` - report options.
+fn render_input_sources<R: ReportRenderer>(renderer: &mut R, input_files: &[String], opts: &ReportOptions) {
+    let mut sources = vec![];
+    for input_file in input_files {
+        match fs::read_to_string(input_file) {
+            Ok(source) => sources.push((input_file, source)),
+            Err(err) => warn(opts, &format!("Can't embed source of {}: {}", input_file, err)),
+        }
+    }
+
+    if sources.is_empty() {
+        return;
+    }
+
+    renderer.section("Sources");
+    for (input_file, source) in sources {
+        let source = match opts.tail_output {
+            Some(lines) => tail_lines(&normalize_newlines(&source), lines),
+            None => source,
+        };
+        renderer.input_source(input_file, &source);
+    }
+}
+
+/// Builds Markdown list of failed tests of all suites, one line each with name and message, see `--failures-snippet`.
+/// Names are shown without namespace, along with the class if there's one.
+/// Messages are cut to their first line, the body is used if there's no message.
+///
+/// Arguments:
+/// * `suites` - test suites to list failed tests of.
+/// * `opts` - report options.
+fn failures_snippet(suites: &[TestSuite], opts: &ReportOptions) -> String {
+    let failed_tests: Vec<&TestCase> = suites.iter()
+        .flat_map(|suite| ordered_failures(&suite.testcases, opts))
+        .filter(|test| test.outcome() >= TestOutcome::Failure)
+        .collect();
+
+    let mut snippet = String::new();
+    let shown = failed_tests.len().min(opts.max_failures.unwrap_or(usize::MAX));
+    for test in &failed_tests[..shown] {
+        let mut name = strip_namespace(&test.name, &opts.lang).to_owned();
+        if let Some(classname) = test.classname.as_deref().filter(|classname| !classname.is_empty()) {
+            name = format!("{}.{}", strip_namespace(classname, &opts.lang), name);
+        }
+
+        let result = test.negative_result().unwrap();
+        let message = result.message.as_deref()
+            .or(result.body.as_deref())
+            .and_then(|message| message.lines().map(str::trim).find(|line| !line.is_empty()));
+        match message {
+            Some(message) => snippet.push_str(&format!("* {}: {}\n", escape_inline(&name), escape_inline(message))),
+            None => snippet.push_str(&format!("* {}\n", escape_inline(&name))),
+        }
+    }
+
+    let omitted = failed_tests.len() - shown;
+    if omitted > 0 {
+        snippet.push_str(&format!("* ... and {}\n", pluralize(omitted as u64, "more failure")));
+    }
+
+    return snippet;
+}
+
+/// Renders just the totals table, no headers or details.
+/// The table is the same as in the full report.
+///
+/// Arguments:
+/// * `report` - parsed report to render.
+/// * `renderer` - renderer of the output format.
+fn render_totals<R: ReportRenderer>(report: &InputReport, renderer: &mut R) {
+    match report {
+        InputReport::Single(suite) => renderer.suite_totals(suite),
+        InputReport::Aggregated(report) => renderer.totals(&report.testsuites, &compute_totals(report)),
+    }
+}
+
+/// Renders brief report of several suites.
+/// Prints totals for each suite and reports failed tests of all suites in overview.
+///
+/// Arguments:
+/// * `report` - aggregated report to render.
+/// * `renderer` - renderer of the output format.
+/// * `opts` - report options.
+fn render_suites<R: ReportRenderer>(report: &JunitReport, renderer: &mut R, opts: &ReportOptions) {
+    let totals = compute_totals(report);
+    let suites = &report.testsuites;
+
+    let mut title = report_title(opts, "Aggregated test report");
+    if opts.header_counts {
+        title.push_str(&format!(" ({}, {} failed)", pluralize(totals.tests, "test"), format_count(totals.failed)));
+    }
+
+    renderer.header(&title);
+    if opts.health_badge {
+        renderer.health_badge(health_badge(&totals, opts));
+    }
+
+    if suites.is_empty() {
+        renderer.no_suites();
+        return;
+    }
+
+    if opts.summary_line {
+        renderer.summary_line(&report_summary_line(suites, &totals));
+    }
+    if opts.verbose {
+        // properties of the whole run go first, then the ones all suites agree on
+        if let Some(properties) = &report.properties {
+            renderer.report_properties("Properties:", &properties.properties.iter().collect::<Vec<_>>());
+        }
+        let properties = common_properties(suites);
+        if !properties.is_empty() {
+            renderer.report_properties("Common properties:", &properties);
+        }
+    }
+    if opts.toc {
+        renderer.table_of_contents(&collect_toc_entries(suites, opts));
+    }
+    renderer.totals(suites, &totals);
+    if let Some(baseline) = &opts.baseline {
+        let (added, removed) = (baseline.added_suites(suites), baseline.removed_suites(suites));
+        if !added.is_empty() || !removed.is_empty() {
+            renderer.baseline_suites(&added, &removed);
+        }
+    }
+
+    if opts.details {
+        render_fail_details(renderer, suites, opts);
+    }
+}
+
+/// Collects properties that all suites have with the same value, in order of the first suite.
+/// Returns nothing if some suite has no properties at all.
+///
+/// Arguments:
+/// * `suites` - test suites of the report.
+fn common_properties(suites: &[TestSuite]) -> Vec<&TestProperty> {
+    let first = match suites.first().and_then(|suite| suite.properties.as_ref()) {
+        Some(properties) => &properties.properties,
+        None => return vec![],
+    };
+
+    return first.iter()
+        .filter(|prop| suites[1..].iter().all(|suite| has_property(suite, prop)))
+        .collect();
+}
+
+/// Checks whether the suite has the property with the same name and value.
+fn has_property(suite: &TestSuite, prop: &TestProperty) -> bool {
+    return suite.properties.as_ref()
+        .map(|properties| properties.properties.iter().any(|other| other.name == prop.name && other.value == prop.value))
+        .unwrap_or(false);
+}
+
+/// Builds a sentence summarizing the aggregated report,
+/// e.g. `Processed 2 suites containing 10 tests: 8 passed, 1 failed, 1 skipped.`
+/// Disabled tests are counted as skipped, same as in the summary printed to stderr.
+///
+/// Arguments:
+/// * `suites` - test suites of the report.
+/// * `totals` - totals of the whole report.
+fn report_summary_line(suites: &[TestSuite], totals: &ReportTotals) -> String {
+    return format!("Processed {} containing {}: {} passed, {} failed, {} skipped.",
+                   pluralize(suites.len() as u64, "suite"),
+                   pluralize(totals.tests, "test"),
+                   format_count(totals.success),
+                   format_count(totals.failed),
+                   format_count(totals.skipped + totals.disabled));
+}
+
+/// Collects entries of the table of contents, suites with most failures go first.
+/// Failing suites link to their first failure in details section, passing ones go last without links.
+///
+/// Arguments:
+/// * `suites` - test suites of the report.
+/// * `opts` - report options.
+fn collect_toc_entries<'a>(suites: &'a [TestSuite], opts: &ReportOptions) -> Vec<TocEntry<'a>> {
+    // failures are numbered across all suites in the details section
+    let mut entries = vec![];
+    let mut fail_index = 0;
+    for suite in suites {
+        let mut first_failure = None;
+        for test in ordered_failures(&suite.testcases, opts) {
+            // skips have details too, but the link should lead to an actual failure
+            if first_failure.is_none() && test.outcome() >= TestOutcome::Failure {
+                first_failure = Some(fail_index);
+            }
+            fail_index += 1;
+        }
+
+        let failed = compute_suite_totals(suite).failed;
+        if !opts.details || failed == 0 || !is_failure_shown(first_failure.unwrap_or(0), opts) {
+            // nowhere to link to
+            first_failure = None;
+        }
+        entries.push(TocEntry { suite, failed, first_failure });
+    }
+    // stable sort keeps suites with the same count in report order
+    entries.sort_by_key(|entry| cmp::Reverse(entry.failed));
+
+    return entries;
+}
+
+/// Adds list of suites with their failure counts, see `collect_toc_entries`.
+///
+/// Arguments:
+/// * `md` - markdown document to append to.
+/// * `entries` - entries of the table of contents, in order.
+/// * `opts` - report options.
+fn add_table_of_contents(md: &mut String, entries: &[TocEntry], opts: &ReportOptions) {
+    md.push('\n');
+    for entry in entries {
+        let name = escape_inline(entry.suite.short_name(&opts.lang));
+        let link = match entry.first_failure {
+            Some(fail_index) => format!("[{}](#{})", name, failure_anchor(fail_index)),
+            None => name,
+        };
+        match entry.failed {
+            0 => md.push_str(&format!("- {} (no failures)\n", link)),
+            _ => md.push_str(&format!("- {} ({})\n", link, pluralize(entry.failed, "failure"))),
+        }
+    }
+}
+
+/// Returns overall health of the report: red if anything failed,
+/// yellow if some tests were skipped (red with `--skips-as-failures`) and green otherwise.
+///
+/// Arguments:
+/// * `totals` - totals of the report.
+/// * `opts` - report options.
+fn health_badge(totals: &ReportTotals, opts: &ReportOptions) -> &'static str {
+    if totals.failed > 0 {
+        return "🔴 Some tests failed";
+    }
+
+    if totals.skipped + totals.disabled > 0 {
+        if opts.skips_as_failures {
+            return "🔴 Some tests were skipped";
+        }
+        return "🟡 Some tests were skipped";
+    }
+
+    return "🟢 All tests passed";
+}
+
+/// Builds a line with counts of test results of the suite, each marked with its status symbol,
+/// e.g. `✓ 48 passed · ✗ 2 failed · ✂ 1 skipped`. Results without tests are left out, except passed ones.
+///
+/// Arguments:
+/// * `suite` - test suite to count results of.
+/// * `opts` - report options with status symbols.
+fn suite_status_line(suite: &TestSuite, opts: &ReportOptions) -> String {
+    let totals = compute_suite_totals(suite);
+    let symbols = &opts.symbols;
+    let mut parts = vec![format!("{} {} passed", symbols.pass, format_count(totals.success))];
+    if totals.failed > 0 {
+        parts.push(format!("{} {} failed", symbols.fail, format_count(totals.failed)));
+    }
+    if totals.skipped > 0 {
+        parts.push(format!("{} {} skipped", symbols.skip, format_count(totals.skipped)));
+    }
+    if totals.disabled > 0 {
+        parts.push(format!("{} {} disabled", symbols.disabled, format_count(totals.disabled)));
+    }
+
+    return parts.join(" · ");
+}
+
+/// Renders detailed report of a single suite.
+/// Prints totals for the suite, status for every test case and reports failed tests in overview.
+///
+/// Arguments:
+/// * `suite` - test suite to report
+/// * `renderer` - renderer of the output format.
+/// * `opts` - report options.
+fn render_suite<R: ReportRenderer>(suite: &TestSuite, renderer: &mut R, opts: &ReportOptions) {
+    let mut title = report_title(opts, opts.file_title.as_deref().unwrap_or_else(|| suite.short_name(&opts.lang)));
+    if opts.header_counts {
+        title.push_str(&format!(" ({})", pluralize(suite.tests, "test")));
+    }
+
+    renderer.header(&title);
+    if opts.health_badge {
+        renderer.health_badge(health_badge(&compute_suite_totals(suite), opts));
+    }
+    if opts.suite_status {
+        renderer.suite_status(&suite_status_line(suite, opts));
+    }
+    if opts.verbose {
+        // there can be lots of properties, only report them in verbose mode
+        renderer.suite_properties(suite);
+    }
+    renderer.section("Overall status");
+    renderer.suite_totals(suite);
+    if opts.by_class && !suite.testcases.is_empty() {
+        renderer.section("Breakdown by classes");
+        renderer.class_summary(&count_by_class(&suite.testcases));
+    }
 
-        // not an aggregated report, deserialize into singular
-        let singular: Result<TestSuite, XmlError> = from_reader(junit_content.as_bytes());
-        if singular.is_ok() {
-            // that's real singular testcase, report it
-            let md = suite_to_md_single(singular.unwrap());
-            println!("{}", md);
-            return;
-        } else {
-            eprintln!("Couldn't parse JUnit XML as singular: {}", singular.unwrap_err());
-            return;
+    renderer.section("Breakdown by testcases");
+    let mut anchors = DocumentAnchors::new(ordered_failures(&suite.testcases, opts));
+    let mut rows = vec![];
+    for test in &suite.testcases {
+        let mut row = TestcaseRow { test, anchor: None, failure: None, failure_shown: false };
+        if opts.row_anchors {
+            row.anchor = Some(anchors.next_row(&test.name));
+        }
+        if let Some(fail_index) = anchors.failure(test) {
+            row.failure = Some(fail_index);
+            row.failure_shown = opts.details && is_failure_shown(fail_index, opts);
         }
+        rows.push(row);
     }
+    renderer.testcase_summary(&rows);
 
-    // there are multiple files, report them as aggregated
-    let mut testsuites: Vec<TestSuite> = vec![];
-    for junit_file in junit_files {
-        // it must be a single file
-        let junit_content = fs::read_to_string(junit_file).expect(&format!("Can't read JUnit file {}", junit_file));
-        let singular: Result<TestSuite, XmlError> = from_reader(junit_content.as_bytes());
-        if singular.is_ok() {
-            testsuites.push(singular.unwrap());
-        } else {
-            eprintln!("Couldn't parse JUnit XML {} as singular: {}", junit_file, singular.unwrap_err());
+    if opts.sparkline {
+        let sparkline = durations_sparkline(&suite.testcases);
+        if !sparkline.is_empty() {
+            renderer.durations(&sparkline);
         }
     }
-
-    // now post an aggregated report
-    let md = suites_to_md_mult(testsuites);
-    println!("{}", md);
+    if opts.verbose_passing {
+        render_passed_tests_output(renderer, &suite.testcases);
+    }
+    if opts.details {
+        render_fail_details(renderer, slice::from_ref(suite), opts);
+    }
+    if opts.show_flaky {
+        render_flaky_tests(renderer, &suite.testcases);
+    }
+    if opts.list_skips {
+        render_skipped_list(renderer, &suite.testcases);
+    }
 }
 
-/// Converts multiple suites to markdown, consuming them. 
-/// Only prints totals for each test suite and only reports failed test cases in the overview.
-/// 
+/// Checks test suite for duplicate test case names and warns about them.
+/// Duplicates usually mean broken test parameterization.
+///
 /// Arguments:
-/// * `suites` - test suites to report.
-fn suites_to_md_mult(suites: Vec<TestSuite>) -> String {
-    let mut md = String::new();
+/// * `suite` - test suite to check.
+/// * `opts` - report options.
+fn validate_suite(suite: &TestSuite, opts: &ReportOptions) {
+    let mut name_counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for test in &suite.testcases {
+        *name_counts.entry(&test.name).or_insert(0) += 1;
+    }
 
-    create_h1(&mut md, "Aggregated test report");
-    add_totals_multiple(&mut md, &suites);
+    for (name, count) in name_counts {
+        if count > 1 {
+            warn(opts, &format!("Suite {}: test case {} occurs {} times", suite.display_name(), name, count));
+        }
+    }
+}
 
-    let failed_tests: Vec<TestCase> = suites.into_iter()
-                             .map(|suite| suite.testcases)
-                             .flatten()
-                             .filter(|test| test.skipped.is_some() || !test.failures.is_empty() || !test.errors.is_empty())
-                             .collect();
-                             
-    add_testcases_fail_details(&mut md, &failed_tests);
+/// Checks for test cases that are reported in several suites at once and warns about them.
+/// Test cases are identified by both class name and name, as same test names in different classes are common.
+///
+/// Arguments:
+/// * `suites` - test suites to check.
+/// * `opts` - report options.
+fn validate_across_suites(suites: &[TestSuite], opts: &ReportOptions) {
+    let mut test_suites: BTreeMap<(&str, &str), BTreeSet<&str>> = BTreeMap::new();
+    for suite in suites {
+        for test in &suite.testcases {
+            let classname = test.classname.as_deref().unwrap_or_default();
+            test_suites.entry((classname, &test.name)).or_default().insert(suite.display_name());
+        }
+    }
 
-    return md;
+    for ((classname, name), suite_names) in test_suites {
+        if suite_names.len() > 1 {
+            let full_name = if classname.is_empty() {
+                name.to_owned()
+            } else {
+                format!("{}.{}", classname, name)
+            };
+            let suite_list: Vec<&str> = suite_names.into_iter().collect();
+            warn(opts, &format!("Test case {} occurs in {} suites: {}", full_name, suite_list.len(), suite_list.join(", ")));
+        }
+    }
 }
 
-/// Converts single suite to markdown, consuming it. 
-/// Prints totals for the suite, status for every test case and reports failed tests in overview.
-/// 
+/// Reads title of the report from the first non-empty line of the file, without Markdown heading marker.
+/// Unreadable file or file without any text is a fatal error, so this exits the process on failure.
+///
 /// Arguments:
-/// * `suite` - test suite to report
-fn suite_to_md_single(suite: TestSuite) -> String {
-    let mut md = String::new();
+/// * `title_file` - path to the file with the title.
+fn read_title_file(title_file: &str) -> String {
+    let content = match fs::read_to_string(title_file) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("Can't read title file {}: {}", title_file, err);
+            process::exit(1);
+        }
+    };
 
-    create_h1(&mut md, omit_java_package(&suite.name));
-    add_suite_properties(&mut md, &suite);
-    add_totals_singular(&mut md, &suite);
-    add_testcases_summary(&mut md, &suite);
-    add_testcases_fail_details(&mut md, &suite.testcases);
+    let title = content.lines()
+        .map(|line| line.trim().trim_start_matches('#').trim())
+        .find(|line| !line.is_empty());
+    match title {
+        Some(title) => return title.to_owned(),
+        None => {
+            eprintln!("Title file {} is empty", title_file);
+            process::exit(1);
+        }
+    }
+}
 
-    return md;
+/// Picks the title for the report. Title from options takes precedence over the derived one.
+///
+/// Arguments:
+/// * `opts` - report options to get custom title from.
+/// * `derived` - title derived from the report content.
+fn report_title(opts: &ReportOptions, derived: &str) -> String {
+    return opts.title.as_deref().unwrap_or(derived).to_owned();
 }
 
-/// Adds suite properties section to the report.
-/// There can be lots of them so it only does so if `IS_VERBOSE` flag is set.
+/// Adds suite properties section to the report, along with host and start time of the suite.
 /// 
 /// Arguments:
 /// * `md` - the report to add properties section to.
 /// * `suite` - test suite to get properties from.
-fn add_suite_properties(md: &mut String, suite: &TestSuite) {
-    if !IS_VERBOSE.load(Ordering::Relaxed) {
-        // not needed in verbose mode, bail out
-        return;
-    }
+/// * `opts` - report options.
+fn add_suite_properties(md: &mut String, suite: &TestSuite, opts: &ReportOptions) {
+    if let (Some(timestamp), Some(hostname), Some(time)) = (&suite.timestamp, &suite.hostname, &suite.time) {
+        let mut timestamp = timestamp.to_owned();
+        if opts.relative_time {
+            if let Some(elapsed) = time_since(&timestamp) {
+                timestamp.push_str(&format!(" ({})", format_time_ago(elapsed)));
+            }
+        }
 
-    // verbose mode is on, report all the details
-    if suite.timestamp.is_some() && suite.hostname.is_some() && suite.time.is_some() {
         md.push('\n');
         md.push_str(&format!("Testset was started on host {hostname} at {timestamp} and took {time} seconds to finish.", 
-            hostname=suite.hostname.as_ref().unwrap(), 
-            timestamp=suite.timestamp.as_ref().unwrap(), 
-            time=suite.time.as_ref().unwrap())
+            hostname=escape_inline(hostname), 
+            timestamp=escape_inline(&timestamp), 
+            time=escape_inline(time))
         );
         md.push('\n');
     }
 
-    if suite.properties.is_none() {
-        return;
+    if let Some(properties) = &suite.properties {
+        add_properties_list(md, "Properties:", &properties.properties.iter().collect::<Vec<_>>());
     }
-    
+}
+
+/// Adds labeled list of properties, multi-line values go to code blocks.
+///
+/// Arguments:
+/// * `md` - the report to add the list to.
+/// * `label` - line shown above the list.
+/// * `properties` - properties to list.
+fn add_properties_list(md: &mut String, label: &str, properties: &[&TestProperty]) {
     md.push('\n');
-    md.push_str("Properties:");
+    md.push_str(label);
+
+    for prop in properties {
+        let value = prop.value.as_deref().unwrap_or_default();
+        if value.contains('\n') {
+            // multi-line values don't fit into a list item
+            md.push('\n');
+            create_code_block(md, &escape_inline(&prop.name), value);
+            continue;
+        }
 
-    let desc = suite.properties.as_ref().unwrap();
-    for prop in &desc.properties {
         md.push('\n');
-        md.push_str(&format!("* {name}: {value}", name=prop.name, value=prop.value));
+        md.push_str(&format!("* {name}: {value}", name=escape_inline(&prop.name), value=escape_inline(value)));
     }
     md.push('\n');
 }
 
-/// Adds summary table for testcases.
-/// Each test is reported and failing tests have a link to see their details.
-/// 
+/// Parses suite timestamp.
+/// Timestamps without time zone are assumed to be in local time, as JUnit writes them.
+/// Returns `None` if the timestamp can't be parsed.
+///
 /// Arguments:
-/// * `md` - the report to add testcase summary section to.
-/// * `suite` - test suite to get tests.
-fn add_testcases_summary(md: &mut String, suite: &TestSuite) {
-    create_h2(md, "Breakdown by testcases");
-
-    let tests = &suite.testcases;
-    let mut table: Vec<Vec<Box<dyn Display>>> = vec![];
-    table.push(vec![
-        Box::new("Testcase name"),
-        Box::new("Status"), 
-        Box::new("Time"),
-        Box::new("Cause"),
-    ]);
-
-    // iterate over each test case and add a row with the description to the table
-    let mut fail_index = 0;
-    for test in tests {
-        let name = omit_java_package(&test.name).to_owned();
-        let test_time = test.time.to_owned().unwrap_or_default();
-
-        if !test.errors.is_empty() {
-            // this is a test with error
-            table.push(vec![
-                Box::new(name),
-                Box::new("‼"), 
-                Box::new(test_time),
-                Box::new(format!("[[{0}]](#c-{0})", fail_index))
-            ]);
-            fail_index += 1;
-            continue;
-        }
+/// * `timestamp` - ISO 8601 timestamp from the report.
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(timestamp) {
+        return Some(time.with_timezone(&Utc));
+    }
 
-        if !test.failures.is_empty() {
-            // this is a test with failure
-            table.push(vec![
-                Box::new(name),
-                Box::new("✗"), 
-                Box::new(test_time),
-                Box::new(format!("[[{0}]](#c-{0})", fail_index))
-            ]);
-            fail_index += 1;
-            continue;
-        }
+    let time = NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f").ok()?;
+    let time = Local.from_local_datetime(&time).earliest()?;
+    return Some(time.with_timezone(&Utc));
+}
 
-        if test.skipped.is_some() {
-            // this is a skipped test
-            table.push(vec![
-                Box::new(name),
-                Box::new("✂"), 
-                Box::new(test_time),
-                Box::new(format!("[[{0}]](#c-{0})", fail_index))
-            ]);
-            fail_index += 1;
-            continue;
-        }
+/// Computes time passed since the suite timestamp, see `parse_timestamp`.
+///
+/// Arguments:
+/// * `timestamp` - ISO 8601 timestamp from the report.
+fn time_since(timestamp: &str) -> Option<chrono::Duration> {
+    let time = parse_timestamp(timestamp)?;
+    return Some(Utc::now().signed_duration_since(time));
+}
 
-        // this is a successful test
-        table.push(vec![
-            Box::new(name),
-            Box::new("✓"), 
-            Box::new(test_time),
-            Box::new(""),
-        ]);
+/// Formats suite start time for the totals table: in UTC, or as time passed since then with `--relative-time`.
+/// Timestamps that can't be parsed are shown as is.
+///
+/// Arguments:
+/// * `timestamp` - ISO 8601 timestamp from the report.
+/// * `opts` - report options.
+fn format_suite_start(timestamp: &str, opts: &ReportOptions) -> String {
+    let time = match parse_timestamp(timestamp) {
+        Some(time) => time,
+        None => return timestamp.to_owned(),
+    };
+
+    if opts.relative_time {
+        return format_time_ago(Utc::now().signed_duration_since(time));
+    }
+
+    return time.format("%Y-%m-%d %H:%M:%S UTC").to_string();
+}
+
+/// Formats elapsed time as a human-readable phrase, e.g. "2 hours ago".
+/// Only the largest unit is used, as precision doesn't matter here.
+///
+/// Arguments:
+/// * `elapsed` - time passed since the event.
+fn format_time_ago(elapsed: chrono::Duration) -> String {
+    let units = [
+        (elapsed.num_days(), "day"),
+        (elapsed.num_hours(), "hour"),
+        (elapsed.num_minutes(), "minute"),
+    ];
+
+    for (count, unit) in units {
+        match count {
+            0 => continue,
+            1 => return format!("1 {} ago", unit),
+            _ if count > 0 => return format!("{} {}s ago", count, unit),
+            // clocks of CI host and the current one may disagree
+            _ => return "in the future".to_owned(),
+        }
     }
-    create_md_table(md, table, true);
+
+    return "just now".to_owned();
+}
+
+/// Adds testcase breakdown table to the report.
+/// Each row has test name, status and time, tests with failure details link to them.
+///
+/// Arguments:
+/// * `md` - the report to add testcase summary section to.
+/// * `rows` - rows of the table, one for each test.
+/// * `opts` - report options.
+fn add_testcases_summary(md: &mut String, rows: &[TestcaseRow], opts: &ReportOptions) {
+    // test and class names go through the same escaping, both can be long and full of Markdown syntax
+    let escape_name = |name: &str| escape_inline(&insert_soft_breaks(name, opts.soft_break_width));
+    let table = testcase_rows(rows, opts, &MD_TESTCASE_CELLS, |name| format_short_name(name, &opts.lang, escape_name));
+    create_md_table_with_width(md, table, true, opts.table_width);
 }
 
 /// Adds summary table for a single testsuite.
@@ -242,197 +2315,565 @@ fn add_testcases_summary(md: &mut String, suite: &TestSuite) {
 /// Arguments:
 /// * `md` - the report to add testcase summary section to.
 /// * `suite` - test suite to get tests.
-fn add_totals_singular(md: &mut String, suite: &TestSuite) {
-    create_h2(md, "Overall status");
-
-    let mut table: Vec<Vec<Box<dyn Display>>> = vec![];
-    table.push(vec![
-        Box::new("Type"),
-        Box::new("Number of tests"),
-        Box::new("% of total")
-    ]);
-
-    let skipped_tests = suite.skipped.unwrap_or(0);
-    table.push(vec![
-        Box::new("Skipped"),
-        Box::new(skipped_tests),
-        Box::new(skipped_tests * 100 / suite.tests)
-    ]);
-
-    let disabled_tests = suite.disabled.unwrap_or(0);
-    table.push(vec![
-        Box::new("Disabled"),
-        Box::new(disabled_tests),
-        Box::new(disabled_tests * 100 / suite.tests)
-    ]);
-
-    let failed_tests = suite.failures.unwrap_or(0) + suite.errors.unwrap_or(0);
-    table.push(vec![
-        Box::new("Failed"),
-        Box::new(failed_tests),
-        Box::new(failed_tests * 100 / suite.tests)
-    ]);
-
-    let success_tests = suite.tests - failed_tests - disabled_tests - skipped_tests;
-    table.push(vec![
-        Box::new("**Success**"),
-        Box::new(success_tests),
-        Box::new(success_tests * 100 / suite.tests)
-    ]);
-
-    create_md_table(md, table, false);
-}
-
-/// Adds details for failed testcases.
+/// * `opts` - report options.
+fn add_totals_singular(md: &mut String, suite: &TestSuite, opts: &ReportOptions) {
+    let table = suite_totals_rows(suite, opts, &MD_TOTALS_CELLS);
+    create_md_table_with_width(md, table, false, opts.table_width);
+
+    if !count_success(suite).1 {
+        add_inconsistent_totals_note(md, &[suite], opts);
+    }
+}
+
+/// Renders details for failed testcases.
 /// Each testcase is reported along with its output and content of failure.
-/// 
+/// If there are several suites, each testcase also notes the suite it came from.
+///
 /// Arguments:
-/// * `md` - the report to add testcase summary section to.
-/// * `tests` - tests that should be reported. Successful ones are skipped.
-fn add_testcases_fail_details(md: &mut String, tests: &Vec<TestCase>) {
+/// * `renderer` - renderer of the output format.
+/// * `suites` - suites with tests that should be reported. Successful ones are skipped.
+/// * `opts` - report options.
+fn render_fail_details<R: ReportRenderer>(renderer: &mut R, suites: &[TestSuite], opts: &ReportOptions) {
+    let tests: Vec<(usize, &TestSuite, &TestCase)> = suites.iter()
+        .enumerate()
+        .flat_map(|(suite_index, suite)| ordered_failures(&suite.testcases, opts).into_iter().map(move |test| (suite_index, suite, test)))
+        .collect();
+
     // no failures to report
-    if !tests.iter().any(|test| test.skipped.is_some() || !test.failures.is_empty() || !test.errors.is_empty()) {
+    if tests.is_empty() {
         return;
     }
 
-    create_h2(md, "Failures");
+    renderer.section("Failures");
+
+    let omitted = tests.len() - tests.len().min(opts.max_failures.unwrap_or(usize::MAX));
+    let mut previous_suite = None;
+    for (index, (suite_index, suite, test)) in tests.into_iter().enumerate().take_while(|(index, _)| is_failure_shown(*index, opts)) {
+        // single suite is already in the title, several ones may differ only by package
+        let suite_name = match suites.len() {
+            1 => None,
+            _ => Some(suite.display_name()),
+        };
+
+        // first failure of each suite is the target of its link in the totals table
+        let suite_anchor = match suites.len() {
+            1 => None,
+            _ if previous_suite == Some(suite_index) => None,
+            _ => Some(suite_anchor(suite_index)),
+        };
+        previous_suite = Some(suite_index);
+
+        // this is a test with error, failure or skip, whichever is the most severe
+        let result = test.negative_result().unwrap();
+        renderer.failure_detail(&FailureDetail { index, suite_name, suite_anchor, test, result });
+    }
+
+    if omitted > 0 {
+        renderer.omitted_failures(omitted as u64);
+    }
+}
+
+/// Collects tests of a suite that have failure details, in the order they are reported, see `--sort-failures`.
+/// Failures are numbered in this order, so links to them from the breakdown table stay aligned.
+/// Suites are never mixed, failures of each suite are ordered separately.
+///
+/// Arguments:
+/// * `tests` - testcases of the suite.
+/// * `opts` - report options.
+fn ordered_failures<'a>(tests: &'a [TestCase], opts: &ReportOptions) -> Vec<&'a TestCase> {
+    let mut failures: Vec<&TestCase> = tests.iter().filter(|test| has_fail_details(test, opts)).collect();
+    let class_name = |test: &TestCase| strip_namespace(test.classname.as_deref().unwrap_or_default(), &opts.lang).to_owned();
+    let test_time = |test: &TestCase| test.time.as_deref().and_then(|time| time.trim().parse::<f64>().ok()).unwrap_or(0.0);
+
+    // stable sorts keep tests with the same key in report order
+    match opts.sort_failures {
+        FailureOrder::Input => {}
+        FailureOrder::Class => failures.sort_by(|a, b| class_name(a).cmp(&class_name(b)).then_with(|| a.name.cmp(&b.name))),
+        FailureOrder::Name => failures.sort_by(|a, b| a.name.cmp(&b.name)),
+        FailureOrder::Time => failures.sort_by(|a, b| test_time(b).total_cmp(&test_time(a))),
+    }
+
+    return failures;
+}
 
+/// Returns anchors of failures of each suite in the aggregated report, see `render_fail_details`.
+/// Suites without failure details shown have no anchor, so they are not linked.
+///
+/// Arguments:
+/// * `suites` - test suites of the report.
+/// * `opts` - report options.
+fn suite_failure_anchors(suites: &[TestSuite], opts: &ReportOptions) -> Vec<Option<String>> {
     let mut fail_index = 0;
-    for test in tests {
-        if !test.errors.is_empty() {
-            let error = &test.errors[0];
+    let mut anchors = Vec::with_capacity(suites.len());
+    for (suite_index, suite) in suites.iter().enumerate() {
+        let failures = suite.testcases.iter().filter(|test| has_fail_details(test, opts)).count();
+        let anchor = if opts.details && suites.len() > 1 && failures > 0 && is_failure_shown(fail_index, opts) {
+            Some(suite_anchor(suite_index))
+        } else {
+            None
+        };
+        anchors.push(anchor);
+        fail_index += failures;
+    }
 
-            // this is a test with error
-            report_negative_result(md, fail_index, test, error);
-            fail_index += 1;
-            continue;
-        }
+    return anchors;
+}
 
-        if !test.failures.is_empty() {
-            let failure = &test.failures[0];
+/// Checks whether details of the failure with the given document-wide index are shown, see `--max-failures`.
+///
+/// Arguments:
+/// * `fail_index` - index of the failure in failure details.
+/// * `opts` - report options.
+fn is_failure_shown(fail_index: usize, opts: &ReportOptions) -> bool {
+    return opts.max_failures.is_none_or(|max_failures| fail_index < max_failures);
+}
 
-            // this is a test with failure
-            report_negative_result(md, fail_index, test, failure);
-            fail_index += 1;
-            continue;
-        }
+/// Renders section with stdout and stderr of passed tests, to help debugging flaky ones.
+/// Tests without any output are omitted, as is the whole section if there are none with output.
+///
+/// Arguments:
+/// * `renderer` - renderer of the output format.
+/// * `tests` - testcases to take output from.
+fn render_passed_tests_output<R: ReportRenderer>(renderer: &mut R, tests: &[TestCase]) {
+    let passed_tests: Vec<&TestCase> = tests.iter()
+        .filter(|test| test.outcome() == TestOutcome::Success)
+        .filter(|test| test.system_out.is_some() || test.system_err.is_some())
+        .collect();
 
-        if let Some(skipped_desc) = &test.skipped {
-            // this is a skipped test
-            report_negative_result(md, fail_index, test, skipped_desc);
-            fail_index += 1;
-            continue;
+    if passed_tests.is_empty() {
+        return;
+    }
+
+    renderer.section("Output of passed tests");
+
+    for test in passed_tests {
+        renderer.test_output(test);
+    }
+}
+
+/// Adds stdout and stderr of the test under its name.
+///
+/// Arguments:
+/// * `md` - markdown document to append to.
+/// * `test` - testcase to take output from.
+/// * `opts` - report options.
+fn add_test_output(md: &mut String, test: &TestCase, opts: &ReportOptions) {
+    create_h3(md, &escape_inline(&test.name));
+    md.push('\n');
+
+    if let Some(out) = &test.system_out {
+        add_code_section(md, opts.labels.stdout(), "Test stdout", out, opts);
+    }
+
+    if let Some(err) = &test.system_err {
+        add_code_section(md, opts.labels.stderr(), "Test stderr", err, opts);
+    }
+}
+
+/// Creates sparkline of test durations, in the order tests are reported.
+/// It's empty if less than two tests have their time reported.
+///
+/// Arguments:
+/// * `tests` - testcases to take durations from.
+fn durations_sparkline(tests: &[TestCase]) -> String {
+    let durations: Vec<Option<f64>> = tests.iter()
+        .map(|test| test.time.as_deref().and_then(|time| time.trim().parse().ok()))
+        .collect();
+
+    return create_sparkline(&durations);
+}
+
+/// Renders section with tests that passed in the end, but failed some of their runs, along with these failures.
+/// Nothing is added if there are no such tests.
+///
+/// Arguments:
+/// * `renderer` - renderer of the output format.
+/// * `tests` - testcases to look for flaky ones in.
+fn render_flaky_tests<R: ReportRenderer>(renderer: &mut R, tests: &[TestCase]) {
+    let flaky_tests: Vec<&TestCase> = tests.iter().filter(|test| test.is_flaky()).collect();
+    if flaky_tests.is_empty() {
+        return;
+    }
+
+    renderer.section("Flaky tests");
+    renderer.summary_line(&format!("{} passed only when rerun, they are counted as successful.",
+                                   pluralize(flaky_tests.len() as u64, "test")));
+    for test in flaky_tests {
+        renderer.flaky_test(test);
+    }
+}
+
+/// Adds failed runs of a flaky test under its name.
+///
+/// Arguments:
+/// * `md` - markdown document to append to.
+/// * `test` - flaky testcase.
+/// * `opts` - report options.
+fn add_flaky_test(md: &mut String, test: &TestCase, opts: &ReportOptions) {
+    create_h3(md, &escape_inline(&test.name));
+    md.push('\n');
+
+    for (index, rerun) in test.rerun_results().enumerate() {
+        let message = rerun.message.as_deref().unwrap_or("Not specified");
+        md.push_str(&format!("* Failed run {}: {}\n", index + 1, create_inline_code(message)));
+        if let Some(stack_trace) = &rerun.stack_trace {
+            add_code_section(md, opts.labels.details(), "Details", stack_trace, opts);
         }
     }
 }
 
+/// Renders section with skipped tests and reasons they were skipped.
+/// Reason is taken from the skip message, or the first line of the skip body if there's no message.
+/// Nothing is added if no tests were skipped.
+///
+/// Arguments:
+/// * `renderer` - renderer of the output format.
+/// * `tests` - testcases to look for skipped ones in.
+fn render_skipped_list<R: ReportRenderer>(renderer: &mut R, tests: &[TestCase]) {
+    let skipped: Vec<SkippedTest> = tests.iter()
+        .filter_map(|test| test.skipped.as_ref().map(|skip| (test, skip)))
+        .map(|(test, skip)| {
+            let reason = skip.message.as_deref()
+                .or_else(|| skip.body.as_deref().and_then(|body| body.lines().next()))
+                .unwrap_or_default();
+            SkippedTest { test, reason: reason.trim() }
+        })
+        .collect();
+
+    if skipped.is_empty() {
+        return;
+    }
+
+    renderer.section("Skipped tests");
+    renderer.skipped_list(&skipped);
+}
+
+/// Adds table of skipped tests and reasons they were skipped.
+///
+/// Arguments:
+/// * `md` - markdown document to append to.
+/// * `skipped` - skipped tests along with their reasons.
+/// * `opts` - report options.
+fn add_skipped_list(md: &mut String, skipped: &[SkippedTest], opts: &ReportOptions) {
+    let mut table = vec![vec!["Testcase name".to_owned(), "Reason".to_owned()]];
+    for SkippedTest { test, reason } in skipped {
+        table.push(vec![escape_inline(strip_namespace(&test.name, &opts.lang)), escape_inline(reason)]);
+    }
+
+    create_md_table_with_width(md, table, true, opts.table_width);
+}
+
+/// Formats name without namespace, see `strip_namespace`. If the namespace was actually omitted,
+/// the full name is shown in a tooltip, so names from different namespaces can be told apart.
+///
+/// Arguments:
+/// * `name` - fully-qualified name of a test or class.
+/// * `langs` - languages to strip namespace of, see `--lang`.
+/// * `escape` - escapes the shortened name for the output format.
+fn format_short_name(name: &str, langs: &[Lang], escape: impl Fn(&str) -> String) -> String {
+    let short_name = strip_namespace(name, langs);
+    if short_name == name {
+        return escape(short_name);
+    }
+
+    return create_tooltip(&escape(short_name), name);
+}
+
+/// Returns exception type of the failed test for the Cause column, without package.
+/// Returns `None` if it's not requested or the report doesn't have it.
+///
+/// Arguments:
+/// * `test` - testcase with failure details.
+/// * `opts` - report options.
+fn format_cause_type(test: &TestCase, opts: &ReportOptions) -> Option<String> {
+    if !opts.cause_type {
+        return None;
+    }
+
+    let error_type = test.negative_result()?.error_type.as_deref()?.trim();
+    if error_type.is_empty() {
+        return None;
+    }
+
+    return Some(strip_namespace(error_type, &opts.lang).to_owned());
+}
+
+/// Formats number of reruns for the Retries column. Retried tests are flagged even if they passed
+/// in the end, as they may be flaky. Tests that don't report it get an empty cell.
+///
+/// Arguments:
+/// * `test` - testcase to get retries of.
+fn format_retries(test: &TestCase) -> String {
+    match test.retry_count() {
+        Some(0) => return "0".to_owned(),
+        Some(retries) => return format!("🔁 {}", retries),
+        None => return String::new(),
+    }
+}
+
+/// Checks whether the test is reported in failure details.
+/// Successful tests never are, negative ones are reported if they're severe enough.
+///
+/// Arguments:
+/// * `test` - testcase to check.
+/// * `opts` - report options with minimal severity to report.
+fn has_fail_details(test: &TestCase, opts: &ReportOptions) -> bool {
+    let outcome = test.outcome();
+    // tests skipped or disabled only by status have nothing to show
+    return outcome != TestOutcome::Success && outcome >= opts.min_severity && test.negative_result().is_some();
+}
+
 /// Helper function that formats a failure result in a human-readable way.
 /// Basically it wraps long content in stdout/stderr and failure bodies into spoilers
 /// that can be expanded by user.
 /// 
 /// Arguments:
 /// * `md` - the report to add testcase summary section to.
-/// * `fail_index` - index of anchor to use. Testcase tables may be referring to this.
-/// * `test` - testcase to report.
-/// * `result` - negative result to report.
-fn report_negative_result(md: &mut String, fail_index: usize, test: &TestCase, result: &TestNegativeResult) {
-    let not_specified = String::from("Not specified");
-
-    md.push_str(&format!("<a id=\"c-{}\"/>\n\n", fail_index));
-    create_h3(md, &test.name);
+/// * `failure` - failed test to report, its index is used for the anchor testcase tables may be referring to.
+/// * `opts` - report options.
+fn report_negative_result(md: &mut String, failure: &FailureDetail, opts: &ReportOptions) {
+    let FailureDetail { index: fail_index, suite_name, ref suite_anchor, test, result } = *failure;
+
+    if let Some(suite_anchor) = suite_anchor {
+        md.push_str(&format!("<a id=\"{}\"/>\n", suite_anchor));
+    }
+    md.push_str(&format!("<a id=\"{}\"/>\n\n", failure_anchor(fail_index)));
+    create_h3(md, &escape_inline(&test.name));
     md.push('\n');
 
+    if let Some(suite_name) = suite_name {
+        md.push_str(&format!("* Suite: {}\n", escape_inline(suite_name)));
+    }
+
     if let Some(classname) = &test.classname {
-        let classname_simple = omit_java_package(classname);
-        md.push_str(&format!("* Classname: {}\n", classname_simple));
+        md.push_str(&format!("* Classname: {}\n", format_short_name(classname, &opts.lang, escape_inline)));
     }
 
-    let failure_message = result.message.as_ref().unwrap_or(&not_specified);
-    md.push_str(&format!("* Fail reason: `{}`\n", failure_message));
+    match failure.reason() {
+        (label, Some(message)) => md.push_str(&format!("* {}: {}\n", label, create_inline_code(message))),
+        (label, None) => md.push_str(&format!("* {}\n", label)),
+    }
 
     if let Some(body) = &result.body {
-        create_code_detail(md, "Click to show details", &body);
+        add_code_section(md, opts.labels.details(), "Details", body, opts);
     }
 
-    if !IS_VERBOSE.load(Ordering::Relaxed) {
+    if !opts.verbose {
         // not verbose, skip stdout/stderr
         return;
     }
 
     if let Some(out) = &test.system_out {
-        create_code_detail(md, "Click to show test stdout", &out);
+        add_code_section(md, opts.labels.stdout(), "Test stdout", out, opts);
     }
 
     if let Some(err) = &test.system_err {
-        create_code_detail(md, "Click to show test stderr", &err);
+        add_code_section(md, opts.labels.stderr(), "Test stderr", err, opts);
+    }
+}
+
+/// Adds long text, such as failure body or test output, to the report.
+/// It's wrapped into collapsible section by default or shown inline if collapsing is turned off.
+///
+/// Arguments:
+/// * `md` - the report to add code section to.
+/// * `summary` - text of the collapsible section summary.
+/// * `label` - label shown above the inline code block.
+/// * `code` - the text to add.
+/// * `opts` - report options.
+fn add_code_section(md: &mut String, summary: &str, label: &str, code: &str, opts: &ReportOptions) {
+    let code = prepare_code(code, opts);
+    if opts.collapse {
+        create_code_detail(md, summary, &code, opts.flavor);
+    } else {
+        create_code_block(md, label, &code);
+    }
+}
+
+/// Formats share of passed tests, see `ReportTotals::pass_rate_base`.
+/// Skipped tests are never excluded if they are treated as failures.
+/// Returns `N/A` if there are no tests to compute it against.
+///
+/// Arguments:
+/// * `totals` - totals of a suite or the whole report.
+/// * `opts` - report options.
+fn format_pass_rate(totals: &ReportTotals, opts: &ReportOptions) -> String {
+    let base = totals.pass_rate_base(opts.exclude_skipped_from_rate && !opts.skips_as_failures);
+    if base == 0 {
+        return "N/A".to_owned();
     }
+
+    return format_percent(totals.success, base);
 }
 
+/// Formatting of totals table cells in Markdown
+const MD_TOTALS_CELLS: TotalsCells = TotalsCells {
+    escape: escape_inline,
+    marker: "⚠[^totals]",
+    emphasize: |label| format!("**{}**", label),
+    link: |text, anchor| format!("[{}](#{})", text, anchor),
+};
+
+/// Formatting of testcase breakdown table cells in Markdown
+const MD_TESTCASE_CELLS: TestcaseCells = TestcaseCells {
+    escape: escape_inline,
+    failure_ref: |fail_index, anchor| match anchor {
+        Some(anchor) => format!("[[{}]](#{})", fail_index, anchor),
+        None => format!("\\[{}\\]", fail_index),
+    },
+    anchor: |anchor| format!("<a id=\"{}\"/>", anchor),
+};
+
 /// Adds summary table for multiple testsuites.
 /// Only numbers of successful/failed/total tests are reported.
 /// 
 /// Arguments:
 /// * `md` - the report to add testcase summary section to.
 /// * `suites` - test suites to get info from.
-fn add_totals_multiple(md: &mut String, suites: &Vec<TestSuite>) {
+/// * `totals` - totals of the whole report, see `compute_totals`.
+/// * `opts` - report options.
+fn add_totals_multiple(md: &mut String, suites: &[TestSuite], totals: &ReportTotals, opts: &ReportOptions) {
     md.push('\n');
 
-    let mut table: Vec<Vec<Box<dyn Display>>> = vec![];
-    table.push(vec![
-        Box::new("Suite name"),
-        Box::new("Time taken, s"),
-        Box::new("Success"),
-        Box::new("Skipped"),
-        Box::new("Disabled"),
-        Box::new("Failures"),
-        Box::new("Total")
-    ]);
-
-
-    let mut skipped_total = 0;
-    let mut disabled_total = 0;
-    let mut failed_total = 0;
-    let mut success_total = 0;
-    let mut overall_total = 0;
-    for suite in suites {
-        let name = omit_java_package(&suite.name).to_owned();
-        let time = suite.time.as_ref().unwrap_or(&String::new()).to_owned();
+    let table = totals_rows(suites, totals, opts, &MD_TOTALS_CELLS);
+    create_md_table_with_width(md, table, true, opts.table_width);
+
+    let inconsistent: Vec<&TestSuite> = suites.iter().filter(|suite| !count_success(suite).1).collect();
+    if !inconsistent.is_empty() {
+        add_inconsistent_totals_note(md, &inconsistent, opts);
+    }
+}
+
+/// Computes totals of the parsed input, whether it's a single suite or aggregated report.
+///
+/// Arguments:
+/// * `report` - parsed input to compute totals for.
+fn compute_input_totals(report: &InputReport) -> ReportTotals {
+    match report {
+        InputReport::Single(suite) => return compute_suite_totals(suite),
+        InputReport::Aggregated(report) => return compute_totals(report),
+    }
+}
+
+/// Renders the report as Markdown, the default output format
+struct MarkdownRenderer<'a> {
+    md: String,
+    opts: &'a ReportOptions,
+}
+
+impl<'a> MarkdownRenderer<'a> {
+    fn new(opts: &'a ReportOptions) -> Self {
+        return MarkdownRenderer { md: String::new(), opts };
+    }
+}
+
+impl ReportRenderer for MarkdownRenderer<'_> {
+    fn header(&mut self, title: &str) {
+        create_h1(&mut self.md, &escape_inline(title));
+    }
+
+    fn health_badge(&mut self, badge: &str) {
+        self.md.push_str(&format!("\n{}\n", badge));
+    }
+
+    fn summary_line(&mut self, summary: &str) {
+        self.md.push_str(&format!("\n{}\n", summary));
+    }
+
+    fn suite_status(&mut self, status: &str) {
+        self.md.push_str(&format!("\n{}\n", escape_inline(status)));
+    }
+
+    fn no_suites(&mut self) {
+        self.md.push_str("\nNo test suites found\n");
+    }
+
+    fn suite_properties(&mut self, suite: &TestSuite) {
+        add_suite_properties(&mut self.md, suite, self.opts);
+    }
+
+    fn report_properties(&mut self, label: &str, properties: &[&TestProperty]) {
+        add_properties_list(&mut self.md, label, properties);
+    }
+
+    fn table_of_contents(&mut self, entries: &[TocEntry]) {
+        add_table_of_contents(&mut self.md, entries, self.opts);
+    }
+
+    fn section(&mut self, title: &str) {
+        create_h2(&mut self.md, title);
+    }
+
+    fn suite_totals(&mut self, suite: &TestSuite) {
+        add_totals_singular(&mut self.md, suite, self.opts);
+    }
 
-        let skipped_tests = suite.skipped.unwrap_or(0);
-        let disabled_tests = suite.disabled.unwrap_or(0);
-        let failed_tests = suite.failures.unwrap_or(0) + suite.errors.unwrap_or(0);
-        let success_tests = suite.tests - failed_tests - disabled_tests - skipped_tests;
+    fn totals(&mut self, suites: &[TestSuite], totals: &ReportTotals) {
+        add_totals_multiple(&mut self.md, suites, totals, self.opts);
+    }
 
-        if skipped_tests > 0 {
-            
+    fn baseline_suites(&mut self, added: &[&str], removed: &[&str]) {
+        let escape = |names: &[&str]| names.iter().map(|name| escape_inline(name)).collect::<Vec<String>>().join(", ");
+        if !added.is_empty() {
+            self.md.push_str(&format!("\nNew suites since baseline: {}\n", escape(added)));
         }
+        if !removed.is_empty() {
+            self.md.push_str(&format!("\nRemoved suites since baseline: {}\n", escape(removed)));
+        }
+    }
 
-        table.push(vec![
-            Box::new(name),
-            Box::new(time), 
-            Box::new(success_tests), 
-            Box::new(skipped_tests), 
-            Box::new(disabled_tests), 
-            Box::new(failed_tests), 
-            Box::new(suite.tests)
-        ]);
+    fn class_summary(&mut self, classes: &[ClassCounts]) {
+        let rows = class_counts_rows(classes, |name| format_short_name(name, &self.opts.lang, escape_inline));
+        create_md_table_with_width(&mut self.md, rows, true, self.opts.table_width);
+    }
+
+    fn testcase_summary(&mut self, rows: &[TestcaseRow]) {
+        add_testcases_summary(&mut self.md, rows, self.opts);
+    }
+
+    fn durations(&mut self, sparkline: &str) {
+        self.md.push_str(&format!("Test durations: {}\n\n", sparkline));
+    }
+
+    fn test_output(&mut self, test: &TestCase) {
+        add_test_output(&mut self.md, test, self.opts);
+    }
+
+    fn failure_detail(&mut self, failure: &FailureDetail) {
+        report_negative_result(&mut self.md, failure, self.opts);
+    }
+
+    fn flaky_test(&mut self, test: &TestCase) {
+        add_flaky_test(&mut self.md, test, self.opts);
+    }
+
+    fn omitted_failures(&mut self, count: u64) {
+        self.md.push_str(&format!("\n... and {} (not shown)\n", pluralize(count, "more failure")));
+    }
+
+    fn skipped_list(&mut self, skipped: &[SkippedTest]) {
+        add_skipped_list(&mut self.md, skipped, self.opts);
+    }
+
+    fn input_source(&mut self, path: &str, source: &str) {
+        create_fenced_detail(&mut self.md, &escape_inline(path), source, "xml", self.opts.flavor);
+    }
+
+    fn footer(&mut self, footer: &str) {
+        self.md.push_str(&format!("\n\n*{}*\n", escape_inline(footer)));
+    }
+
+    fn finish(self) -> String {
+        return self.md;
+    }
+}
 
-        skipped_total += skipped_tests;
-        disabled_total += disabled_tests;
-        failed_total += failed_tests;
-        success_total += success_tests;
-        overall_total += suite.tests;
-    }
-
-    table.push(vec![
-        Box::new("**Total**"),
-        Box::new("N/A"), 
-        Box::new(success_total), 
-        Box::new(skipped_total), 
-        Box::new(disabled_total), 
-        Box::new(failed_total), 
-        Box::new(overall_total)
-    ]);
-
-    create_md_table(md, table, true);
-}
\ No newline at end of file
+/// Adds footnote explaining the inconsistent totals marker.
+///
+/// Arguments:
+/// * `md` - the report to add the footnote to.
+/// * `suites` - test suites whose totals don't add up.
+/// * `opts` - report options.
+fn add_inconsistent_totals_note(md: &mut String, suites: &[&TestSuite], opts: &ReportOptions) {
+    md.push_str(&format!("[^totals]: {}\n", inconsistent_totals_note(suites, opts, &MD_TOTALS_CELLS)));
+}
@@ -1,19 +1,34 @@
 
 use std::cmp;
 use std::fmt::Display;
+use std::sync::OnceLock;
 
-/// Creates main header in Markdown
-pub(super) fn create_h1(md: &mut String, title: &str) {
+use regex::Regex;
+
+use serde_derive::Deserialize;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Creates main header in Markdown. Underline matches the displayed width of the title,
+/// so wide glyphs take two columns each:
+///
+/// ```
+/// use junit2md::md::create_h1;
+///
+/// let mut md = String::new();
+/// create_h1(&mut md, "测试报告");
+/// assert_eq!(md, "\n测试报告\n========\n");
+/// ```
+pub fn create_h1(md: &mut String, title: &str) {
     create_header(md, "=", title);
 }
 
 /// Creates secondary header in Markdown
-pub(super) fn create_h2(md: &mut String, title: &str) {
+pub fn create_h2(md: &mut String, title: &str) {
     create_header(md, "-", title);
 }
 
 /// Creates auxiliary header in Markdown
-pub(super) fn create_h3(md: &mut String, title: &str) {
+pub fn create_h3(md: &mut String, title: &str) {
     md.push('\n');
     md.push_str(&format!("### {} ###", title));
     md.push('\n');
@@ -24,21 +39,341 @@ fn create_header(md: &mut String, underline: &str, title: &str) {
     md.push('\n');
     md.push_str(title);
     md.push('\n');
-    md.push_str(&underline.repeat(title.chars().count()));
+    md.push_str(&underline.repeat(title.width()));
     md.push('\n');
 }
 
+/// Formats a count with thousands separators, e.g. `1234567` becomes `1,234,567`.
+pub fn format_count(count: u64) -> String {
+    let digits = count.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(digit);
+    }
+    return result;
+}
+
+/// Formats percentage of `count` in `total` with one decimal place and the `%` sign.
+/// Empty total yields zero percent.
+///
+/// ```
+/// use junit2md::md::format_percent;
+///
+/// assert_eq!(format_percent(1, 2), "50.0%");
+/// assert_eq!(format_percent(1, 3), "33.3%");
+/// assert_eq!(format_percent(0, 0), "0.0%");
+/// ```
+pub fn format_percent(count: u64, total: u64) -> String {
+    if total == 0 {
+        return "0.0%".to_owned();
+    }
+
+    return format!("{:.1}%", count as f64 * 100.0 / total as f64);
+}
+
+/// Formats a count followed by a noun in the right number, e.g. `1 test` or `1,500 tests`.
+///
+/// ```
+/// use junit2md::md::pluralize;
+///
+/// assert_eq!(pluralize(1, "test"), "1 test");
+/// assert_eq!(pluralize(12345, "test"), "12,345 tests");
+/// ```
+pub fn pluralize(count: u64, noun: &str) -> String {
+    match count {
+        1 => return format!("1 {}", noun),
+        _ => return format!("{} {}s", format_count(count), noun),
+    }
+}
+
+/// Escapes characters that have special meaning in inline Markdown,
+/// so user-provided text is rendered verbatim.
+///
+/// ```
+/// use junit2md::md::escape_inline;
+///
+/// assert_eq!(escape_inline("__init__"), r"\_\_init\_\_");
+/// assert_eq!(escape_inline("matches *.xml"), r"matches \*.xml");
+/// assert_eq!(escape_inline("case [1] #2"), r"case \[1\] \#2");
+/// ```
+pub fn escape_inline(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\`*_[]#<>|".contains(c) {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    return result;
+}
+
+/// Wraps text into an inline code span that renders it verbatim.
+/// The delimiter is longer than any backtick run inside the text, so text can't break out of it,
+/// and line breaks are turned into spaces, so multi-line text stays on the line it's put in.
+///
+/// ```
+/// use junit2md::md::create_inline_code;
+///
+/// assert_eq!(create_inline_code("expected *"), "`expected *`");
+/// assert_eq!(create_inline_code("use `x` here"), "``use `x` here``");
+/// assert_eq!(create_inline_code("`quoted`"), "`` `quoted` ``");
+/// assert_eq!(create_inline_code("first\r\nsecond"), "`first second`");
+/// ```
+pub fn create_inline_code(text: &str) -> String {
+    let text = normalize_newlines(text).replace('\n', " ");
+    let delimiter = "`".repeat(longest_backtick_run(&text) + 1);
+    // a leading or trailing backtick would merge with the delimiter, padding keeps them apart
+    if text.starts_with('`') || text.ends_with('`') {
+        return format!("{} {} {}", delimiter, text, delimiter);
+    }
+    return format!("{}{}{}", delimiter, text, delimiter);
+}
+
+/// Markdown dialect to generate. Dialects differ in how they render HTML blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Flavor {
+    /// GitHub-flavored Markdown
+    #[default]
+    Github,
+    /// GitLab-flavored Markdown, needs blank lines around everything inside HTML blocks
+    Gitlab,
+}
+
 /// Creates spoiler tag in Markdown (GFM)
-pub(super) fn create_code_detail(md: &mut String, summary: &str, code: &str) {
+///
+/// GitLab only renders Markdown inside HTML blocks if it's separated by blank lines:
+///
+/// ```
+/// use junit2md::md::{create_code_detail, Flavor};
+///
+/// let mut md = String::new();
+/// create_code_detail(&mut md, "Details", "line 1\nline 2", Flavor::Gitlab);
+/// assert_eq!(md, "<details>\n<summary>Details</summary>\n\n    line 1\n    line 2\n\n</details>\n\n");
+/// ```
+pub fn create_code_detail(md: &mut String, summary: &str, code: &str, flavor: Flavor) {
+    md.push_str("<details>\n");
+    match flavor {
+        Flavor::Github => md.push_str(&format!("    <summary>{}</summary>\n", summary)),
+        Flavor::Gitlab => md.push_str(&format!("<summary>{}</summary>\n", summary)),
+    }
+    md.push('\n');
+    md.push_str(&tabulate(&normalize_newlines(code), "    "));
+    md.push('\n');
+    if flavor == Flavor::Gitlab {
+        md.push('\n');
+    }
+    md.push_str("</details>\n");
+    md.push('\n');
+}
+
+/// Creates spoiler tag in Markdown (GFM) with fenced code block inside, see `create_code_block`.
+/// The info string after the opening fence picks syntax highlighting.
+///
+/// ```
+/// use junit2md::md::{create_fenced_detail, Flavor};
+///
+/// let mut md = String::new();
+/// create_fenced_detail(&mut md, "report.xml", "<testsuite/>", "xml", Flavor::Github);
+/// assert_eq!(md, "<details>\n    <summary>report.xml</summary>\n\n```xml\n<testsuite/>\n```\n\n</details>\n\n");
+/// ```
+pub fn create_fenced_detail(md: &mut String, summary: &str, code: &str, info: &str, flavor: Flavor) {
+    let code = normalize_newlines(code);
+    let fence = create_fence(&code);
+
     md.push_str("<details>\n");
-    md.push_str(&format!("    <summary>{}</summary>\n", summary));
-    md.push_str("\n");
-    md.push_str(&tabulate(&code, "    "));
-    md.push_str("\n");
+    match flavor {
+        Flavor::Github => md.push_str(&format!("    <summary>{}</summary>\n", summary)),
+        Flavor::Gitlab => md.push_str(&format!("<summary>{}</summary>\n", summary)),
+    }
+    md.push('\n');
+    md.push_str(&fence);
+    md.push_str(info);
+    md.push('\n');
+    md.push_str(&code);
+    if !code.ends_with('\n') {
+        md.push('\n');
+    }
+    md.push_str(&fence);
+    md.push_str("\n\n");
     md.push_str("</details>\n");
     md.push('\n');
 }
 
+/// Returns backtick fence longer than any backtick run inside the code, so code can't break out of it.
+fn create_fence(code: &str) -> String {
+    return "`".repeat(cmp::max(3, longest_backtick_run(code) + 1));
+}
+
+/// Creates fenced code block in Markdown with a bold label line above it.
+/// The fence is made longer than any backtick run inside the code, so code can't break out of it.
+pub fn create_code_block(md: &mut String, label: &str, code: &str) {
+    let code = normalize_newlines(code);
+    let fence = create_fence(&code);
+
+    md.push('\n');
+    md.push_str(&format!("**{}:**\n", label));
+    md.push('\n');
+    md.push_str(&fence);
+    md.push('\n');
+    md.push_str(&code);
+    if !code.ends_with('\n') {
+        md.push('\n');
+    }
+    md.push_str(&fence);
+    md.push('\n');
+    md.push('\n');
+}
+
+/// Wraps already escaped text into a span that shows the tooltip on hover.
+/// Both GitHub and GitLab keep `title` attribute of inline HTML.
+///
+/// ```
+/// use junit2md::md::create_tooltip;
+///
+/// assert_eq!(create_tooltip("FooTest", "com.example.FooTest"), r#"<span title="com.example.FooTest">FooTest</span>"#);
+/// assert_eq!(create_tooltip("x", r#"a<"b">"#), r#"<span title="a&lt;&quot;b&quot;&gt;">x</span>"#);
+/// ```
+pub fn create_tooltip(text: &str, tooltip: &str) -> String {
+    let tooltip = tooltip.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+    return format!("<span title=\"{}\">{}</span>", tooltip, text);
+}
+
+/// Creates slug usable in anchor ids and links: lowercase ASCII letters and digits,
+/// everything else is collapsed into single dashes.
+///
+/// ```
+/// use junit2md::md::create_slug;
+///
+/// assert_eq!(create_slug("should_parse [Input #1]"), "should-parse-input-1");
+/// ```
+pub fn create_slug(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        return "untitled".to_owned();
+    }
+
+    return slug.to_owned();
+}
+
+/// Inserts zero-width spaces into runs of text wider than `max_width` columns,
+/// so browsers can wrap them in table cells. Text that already has spaces often enough is left as is.
+///
+/// ```
+/// use junit2md::md::insert_soft_breaks;
+///
+/// assert_eq!(insert_soft_breaks("abcdefgh", 3), "abc\u{200B}def\u{200B}gh");
+/// assert_eq!(insert_soft_breaks("abc def", 3), "abc def");
+/// ```
+pub fn insert_soft_breaks(text: &str, max_width: usize) -> String {
+    if max_width == 0 || text.width() <= max_width {
+        return text.to_owned();
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut run_width = 0;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            // browsers can wrap here already
+            run_width = 0;
+            result.push(c);
+            continue;
+        }
+
+        let char_width = c.width().unwrap_or(0);
+        if run_width > 0 && run_width + char_width > max_width {
+            result.push('\u{200B}');
+            run_width = 0;
+        }
+        run_width += char_width;
+        result.push(c);
+    }
+
+    return result;
+}
+
+/// Converts Windows (`\r\n`) and old Mac (`\r`) line endings to Unix ones.
+///
+/// ```
+/// use junit2md::md::normalize_newlines;
+///
+/// assert_eq!(normalize_newlines("first\r\nsecond\rthird\n"), "first\nsecond\nthird\n");
+/// ```
+pub fn normalize_newlines(text: &str) -> String {
+    return text.replace("\r\n", "\n").replace('\r', "\n");
+}
+
+/// Keeps only the last lines of the text, marking how many lines were cut off at the top.
+/// Text that has no more lines than that is returned as is.
+///
+/// ```
+/// use junit2md::md::tail_lines;
+///
+/// assert_eq!(tail_lines("first\nsecond\nthird\n", 2), "... (1 earlier line omitted)\nsecond\nthird\n");
+/// assert_eq!(tail_lines("first\nsecond", 2), "first\nsecond");
+/// ```
+pub fn tail_lines(text: &str, count: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() <= count {
+        return text.to_owned();
+    }
+
+    let omitted = lines.len() - count;
+    let mut result = format!("... ({} omitted)\n", pluralize(omitted as u64, "earlier line"));
+    result.push_str(&lines[omitted..].join("\n"));
+    if text.ends_with('\n') {
+        result.push('\n');
+    }
+    return result;
+}
+
+/// Removes ANSI escape sequences, such as colors set by test output, from the text.
+///
+/// ```
+/// use junit2md::md::strip_ansi_codes;
+///
+/// assert_eq!(strip_ansi_codes("\x1b[1;31merror\x1b[0m: test \x1b[32mpassed\x1b[m"), "error: test passed");
+/// ```
+pub fn strip_ansi_codes(text: &str) -> String {
+    static ANSI_ESCAPE: OnceLock<Regex> = OnceLock::new();
+    let ansi_escape = ANSI_ESCAPE.get_or_init(|| {
+        // CSI sequences (colors, cursor movement), OSC sequences (titles, links), then two-char escapes
+        Regex::new(r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(\x07|\x1b\\)|\x1b[@-Z\\-_]").unwrap()
+    });
+
+    return ansi_escape.replace_all(text, "").into_owned();
+}
+
+/// Finds length of the longest sequence of consecutive backticks in the text
+fn longest_backtick_run(text: &str) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in text.chars() {
+        if c == '`' {
+            current += 1;
+            longest = cmp::max(longest, current);
+        } else {
+            current = 0;
+        }
+    }
+    return longest;
+}
+
 /// Appends a number of spaces before each newline
 fn tabulate(input: &str, to_prepend: &str) -> String {
     let mut result = input.to_owned();
@@ -46,8 +381,96 @@ fn tabulate(input: &str, to_prepend: &str) -> String {
     return result.replace('\n', &format!("\n{}", to_prepend)); // insert after each newline
 }
 
-/// Creates table in Markdown. Table is passed as a vector of rows, top-to-down, each row is a vector of cells, left-to-right.
-pub(super) fn create_md_table(md: &mut String, rows: Vec<Vec<Box<dyn Display>>>, align_left_first_column: bool) {
+/// Creates a fixed-width bar chart of test results using block characters.
+/// Successful tests are drawn as `█`, skipped as `▒` and failed as `░`.
+/// Returns empty string if there are no tests at all.
+pub fn create_bar_chart(success: u64, skipped: u64, failed: u64, width: usize) -> String {
+    let total = success + skipped + failed;
+    if total == 0 || width == 0 {
+        // nothing to draw
+        return String::new();
+    }
+
+    // round to nearest cell, success takes what's left
+    let width = width as u64;
+    let failed_len = (failed * width * 2 + total) / (total * 2);
+    let skipped_len = cmp::min((skipped * width * 2 + total) / (total * 2), width - failed_len);
+    let success_len = width - failed_len - skipped_len;
+
+    let mut bar = String::new();
+    bar.push_str(&"█".repeat(success_len as usize));
+    bar.push_str(&"▒".repeat(skipped_len as usize));
+    bar.push_str(&"░".repeat(failed_len as usize));
+    return bar;
+}
+
+/// Creates a sparkline of values using block characters from `▁` to `█`, scaled to the largest value.
+/// Missing values are drawn as the lowest block. Returns empty string if less than two values are present.
+///
+/// ```
+/// use junit2md::md::create_sparkline;
+///
+/// assert_eq!(create_sparkline(&[Some(0.0), Some(0.5), None, Some(1.0)]), "▁▅▁█");
+/// assert_eq!(create_sparkline(&[Some(1.0), None]), "");
+/// ```
+pub fn create_sparkline(values: &[Option<f64>]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    if values.iter().flatten().count() < 2 {
+        // one value alone doesn't show any trend
+        return String::new();
+    }
+
+    let max = values.iter().flatten().cloned().fold(0.0, f64::max);
+    return values.iter()
+        .map(|value| match value {
+            Some(value) if max > 0.0 => BLOCKS[(value / max * 7.0).round() as usize],
+            _ => BLOCKS[0],
+        })
+        .collect();
+}
+
+/// Creates table in Markdown. Table is passed as rows, top-to-down, each row is a sequence of cells, left-to-right.
+/// The first row is treated as a header. Anything that implements `Display` can be used as a cell.
+///
+/// ```
+/// use junit2md::md::create_md_table;
+///
+/// let mut md = String::new();
+/// create_md_table(&mut md, vec![["Name", "Result"], ["test_one", "ok"]], true);
+/// assert_eq!(md, "|  Name  |Result|\n|--------|------|\n|test_one|  ok  |\n\n");
+/// ```
+pub fn create_md_table<R, C>(md: &mut String, rows: R, align_left_first_column: bool)
+    where R: IntoIterator,
+          R::Item: IntoIterator<Item = C>,
+          C: Display
+{
+    create_md_table_with_width(md, rows, align_left_first_column, None);
+}
+
+/// Creates table in Markdown, same as `create_md_table`, but with fixed width of all columns if it's given.
+/// Cells wider than that are shortened with an ellipsis, see `truncate_cell`.
+///
+/// ```
+/// use junit2md::md::create_md_table_with_width;
+///
+/// let mut md = String::new();
+/// create_md_table_with_width(&mut md, vec![["Name", "Result"], ["test_number_one", "ok"]], true, Some(8));
+/// assert_eq!(md, "|  Name  | Result |\n|--------|--------|\n|test_nu…|   ok   |\n\n");
+/// ```
+pub fn create_md_table_with_width<R, C>(md: &mut String, rows: R, align_left_first_column: bool, column_width: Option<usize>)
+    where R: IntoIterator,
+          R::Item: IntoIterator<Item = C>,
+          C: Display
+{
+    let rows: Vec<Vec<String>> = rows.into_iter()
+        .map(|row| row.into_iter().map(|cell| cell.to_string()).collect())
+        .map(|row: Vec<String>| match column_width {
+            Some(width) => row.iter().map(|cell| truncate_cell(cell, width)).collect(),
+            None => row,
+        })
+        .collect();
+
     if rows.len() < 2 {
         // we need at least one header row and one value row
         return;
@@ -55,15 +478,14 @@ pub(super) fn create_md_table(md: &mut String, rows: Vec<Vec<Box<dyn Display>>>,
 
     // first, detect column width for each row
     let column_count = rows[0].len();
-    let mut column_widths = vec![3; column_count];
+    let mut column_widths = vec![column_width.unwrap_or(3); column_count];
 
     // detect max column width
     for row in rows.iter() {
-        for index in 0..column_count {
+        for (index, width) in column_widths.iter_mut().enumerate() {
             // from regular rows
             if let Some(cell) = row.get(index) {
-                let text = cell.to_string();
-                column_widths[index] = cmp::max(column_widths[index], text.len());
+                *width = cmp::max(*width, cell.chars().count());
             }
         }
     }
@@ -72,17 +494,15 @@ pub(super) fn create_md_table(md: &mut String, rows: Vec<Vec<Box<dyn Display>>>,
         // make headers
         md.push('|');
         for index in 0..column_count {
-            let header_name = headers[index].to_string();
-            md.push_str(&pad_cell_text(&header_name, column_widths[index], true));
+            md.push_str(&pad_cell_text(&headers[index], column_widths[index], true));
             md.push('|');
         }
         md.push('\n');
 
         // make header-divider row
         md.push('|');
-        for index in 0..column_count {
-            let width = column_widths[index];
-            md.push_str(&"-".repeat(width));
+        for width in &column_widths {
+            md.push_str(&"-".repeat(*width));
             md.push('|');
         }
         md.push('\n');
@@ -90,12 +510,12 @@ pub(super) fn create_md_table(md: &mut String, rows: Vec<Vec<Box<dyn Display>>>,
         for row in data.iter() {
             md.push('|');
             for index in 0..column_count {
-                let cell_text = row[index].to_string();
+                let cell_text = &row[index];
                 if align_left_first_column && index == 0 {
-                    let padded_right_text = pad_cell_text(&cell_text, column_widths[index], false);
+                    let padded_right_text = pad_cell_text(cell_text, column_widths[index], false);
                     md.push_str(&padded_right_text);
                 } else {
-                    let padded_text = pad_cell_text(&cell_text, column_widths[index], true);
+                    let padded_text = pad_cell_text(cell_text, column_widths[index], true);
                     md.push_str(&padded_text);
                 }
                 
@@ -107,17 +527,182 @@ pub(super) fn create_md_table(md: &mut String, rows: Vec<Vec<Box<dyn Display>>>,
     }
 }
 
+/// Shortens cell text to the given display width, replacing the rest with an ellipsis.
+/// Only visible text counts: escape sequences are not split, and links, strong emphasis
+/// and HTML tags are cut inside, keeping their markup balanced.
+///
+/// ```
+/// use junit2md::md::truncate_cell;
+///
+/// assert_eq!(truncate_cell("ConnectionTest", 8), "Connect…");
+/// assert_eq!(truncate_cell(r"test\_one", 6), r"test\_…");
+/// assert_eq!(truncate_cell("[ConnectionTest](#suite)", 8), "[Connect…](#suite)");
+/// assert_eq!(truncate_cell(r"\[ConnectionTest\](#suite)", 8), r"\[Connec…");
+/// assert_eq!(truncate_cell("**Success**", 5), "**Succ…**");
+/// assert_eq!(truncate_cell("テスト名前", 5), "テス…");
+/// ```
+pub fn truncate_cell(text: &str, max_width: usize) -> String {
+    let spans = cell_spans(text);
+    if spans.iter().map(CellSpan::width).sum::<usize>() <= max_width {
+        return text.to_owned();
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut width = 0;
+    let mut truncated = false;
+    let mut strong_open = false;
+    let mut open_tags: usize = 0;
+    // links that start after the ellipsis are dropped along with their ends
+    let mut dropped_links = 0;
+    for span in spans {
+        match span {
+            CellSpan::Text { .. } | CellSpan::Footnote(_) => {
+                if truncated {
+                    continue;
+                }
+                if width + span.width() + 1 > max_width {
+                    truncated = true;
+                    result.push('…');
+                    continue;
+                }
+                width += span.width();
+                result.push_str(span.raw());
+            }
+            CellSpan::LinkStart if truncated => dropped_links += 1,
+            CellSpan::LinkEnd(_) if dropped_links > 0 => dropped_links -= 1,
+            CellSpan::Strong => {
+                if truncated && !strong_open {
+                    continue;
+                }
+                strong_open = !strong_open;
+                result.push_str(span.raw());
+            }
+            CellSpan::Tag(tag) => {
+                let closing = tag.starts_with("</");
+                if truncated && !(closing && open_tags > 0) {
+                    continue;
+                }
+                if closing {
+                    open_tags = open_tags.saturating_sub(1);
+                } else if !tag.ends_with("/>") {
+                    open_tags += 1;
+                }
+                result.push_str(tag);
+            }
+            CellSpan::LinkStart | CellSpan::LinkEnd(_) => result.push_str(span.raw()),
+        }
+    }
+    return result;
+}
+
+/// Piece of Markdown table cell text, see `cell_spans`.
+enum CellSpan<'a> {
+    /// Single character or escape sequence, rendered as the visible character.
+    Text { raw: &'a str, visible: &'a str },
+    /// Footnote reference, e.g. `[^totals]`, rendered as a number.
+    Footnote(&'a str),
+    /// Opening bracket of a link.
+    LinkStart,
+    /// Closing bracket of a link with its target, e.g. `](#suite)`.
+    LinkEnd(&'a str),
+    /// Strong emphasis delimiter.
+    Strong,
+    /// Inline HTML tag.
+    Tag(&'a str),
+}
+
+impl CellSpan<'_> {
+    /// Returns Markdown text of the span.
+    fn raw(&self) -> &str {
+        match self {
+            CellSpan::Text { raw, .. } => return raw,
+            CellSpan::Footnote(raw) | CellSpan::LinkEnd(raw) | CellSpan::Tag(raw) => return raw,
+            CellSpan::LinkStart => return "[",
+            CellSpan::Strong => return "**",
+        }
+    }
+
+    /// Returns how many columns the span takes when rendered, markup takes none.
+    fn width(&self) -> usize {
+        match self {
+            CellSpan::Text { visible, .. } => return UnicodeWidthStr::width(*visible),
+            CellSpan::Footnote(_) => return 1,
+            _ => return 0,
+        }
+    }
+}
+
+/// Splits Markdown cell text into visible characters and markup around them.
+/// Escaped brackets, e.g. in test names, don't make a link.
+fn cell_spans(text: &str) -> Vec<CellSpan<'_>> {
+    let mut spans = Vec::new();
+    // start and end of `](target)` of the link we're inside of
+    let mut link_end: Option<(usize, usize)> = None;
+    let mut index = 0;
+    while index < text.len() {
+        let rest = &text[index..];
+        if let Some((end_start, end)) = link_end {
+            if index == end_start {
+                spans.push(CellSpan::LinkEnd(&text[end_start..end]));
+                link_end = None;
+                index = end;
+                continue;
+            }
+        }
+
+        let char_len = rest.chars().next().map_or(1, char::len_utf8);
+        let (span, len) = if rest.starts_with('\\') && rest.len() > 1 {
+            let len = 1 + rest[1..].chars().next().map_or(1, char::len_utf8);
+            (CellSpan::Text { raw: &rest[..len], visible: &rest[1..len] }, len)
+        } else if rest.starts_with("**") {
+            (CellSpan::Strong, 2)
+        } else if let Some(end) = rest.find(']').filter(|_| rest.starts_with("[^")) {
+            (CellSpan::Footnote(&rest[..=end]), end + 1)
+        } else if let Some((end_start, end)) = find_link_end(rest).filter(|_| link_end.is_none()) {
+            link_end = Some((index + end_start, index + end));
+            (CellSpan::LinkStart, 1)
+        } else if let Some(end) = rest.find('>').filter(|_| rest.starts_with('<')) {
+            (CellSpan::Tag(&rest[..=end]), end + 1)
+        } else {
+            (CellSpan::Text { raw: &rest[..char_len], visible: &rest[..char_len] }, char_len)
+        };
+        spans.push(span);
+        index += len;
+    }
+    return spans;
+}
+
+/// Finds `](target)` closing the link that text starts with.
+/// Returns byte offsets of its start and end, or `None` if text doesn't start with a link.
+fn find_link_end(text: &str) -> Option<(usize, usize)> {
+    if !text.starts_with('[') {
+        return None;
+    }
+
+    let mut escaped = false;
+    for (index, c) in text.char_indices().skip(1) {
+        if c == ']' && !escaped {
+            if !text[index + 1..].starts_with('(') {
+                return None;
+            }
+            return text[index..].find(')').map(|target_end| (index, index + target_end + 1));
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    return None;
+}
+
 /// Pads Markdown cell text so it looks aligned in the table. Not necessary but makes raw Markdown more readable.
-pub(super) fn pad_cell_text(content: &str, column_width: usize, align_center: bool) -> String {
+pub fn pad_cell_text(content: &str, column_width: usize, align_center: bool) -> String {
     let mut result = String::new();
-    if content.len() > 0 {
+    if !content.is_empty() {
         // have header at specified position
         // compute difference between width and text length
-        let len_diff = column_width - content.chars().count();
+        let len_diff = column_width.saturating_sub(content.chars().count());
         if len_diff > 0 {
             // should pad
             if !align_center {
-                result.push_str(&content);
+                result.push_str(content);
                 result.push_str(&" ".repeat(len_diff));
                 return result;
             }
@@ -127,16 +712,16 @@ pub(super) fn pad_cell_text(content: &str, column_width: usize, align_center: bo
                 let pad_len = len_diff / 2;
                 let remainder = len_diff % 2;
                 result.push_str(&" ".repeat(pad_len));
-                result.push_str(&content);
+                result.push_str(content);
                 result.push_str(&" ".repeat(pad_len + remainder));
             } else {
                 // it's just one space, add at the end
-                result.push_str(&content);
+                result.push_str(content);
                 result.push(' ');
             }
         } else {
             // shouldn't pad, text fills whole cell
-            result.push_str(&content);
+            result.push_str(content);
         }
     } else {
         // no text in this cell, fill cell with spaces
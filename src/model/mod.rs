@@ -1,16 +1,35 @@
 use serde_derive::Deserialize;
 
-#[derive(Debug, Deserialize)]
+use crate::lang_specific::{strip_namespace, Lang};
+
+/// Name shown for test suites that don't have one
+pub const UNNAMED_SUITE: &str = "Unnamed suite";
+
+/// Values of `status` attribute that mark a test as skipped, for frameworks that don't emit `<skipped>`
+const SKIPPED_STATUSES: &[&str] = &["skipped", "skip", "notrun"];
+
+#[derive(Debug, Default, Deserialize)]
 pub struct JunitReport {
+    pub name: Option<String>,
+    pub tests: Option<u64>,
+    pub failures: Option<u64>,
+    pub disabled: Option<u64>,
+    pub skipped: Option<u64>,
+    pub errors: Option<u64>,
+    pub time: Option<String>,
     pub duration: Option<f64>,
 
+    /// Properties of the whole run, such as CI build number
+    pub properties: Option<TestProperties>,
+
     #[serde(rename = "testsuite", default)]
     pub testsuites: Vec<TestSuite>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct TestSuite {
-    pub name: String,
+    /// Name of the suite, some custom emitters omit it
+    pub name: Option<String>,
     pub tests: u64,
     pub id: Option<String>,
     pub package: Option<String>,
@@ -35,19 +54,20 @@ pub struct TestSuite {
     pub testcases: Vec<TestCase>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct TestProperties {
     #[serde(rename = "property", default)]
     pub properties: Vec<TestProperty>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct TestProperty {
     pub name: String,
-    pub value: String,
+    /// Either `value` attribute or body of the nested `<value>` element, JUnit 5 uses the latter for multi-line values
+    pub value: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct TestCase {
     pub name: String,
     pub assertions: Option<String>,
@@ -55,6 +75,11 @@ pub struct TestCase {
     pub classname: Option<String>,
     pub status: Option<String>,
 
+    /// Number of times the test was rerun, written by converters that retry failed tests.
+    /// Kept as text, so an empty or malformed count doesn't fail the whole report, see `retry_count`
+    pub retries: Option<String>,
+    /// Number of times the test was run, some converters write it instead of `retries`
+    pub attempts: Option<String>,
 
     #[serde(rename = "system-out")]
     pub system_out: Option<String>,
@@ -63,14 +88,29 @@ pub struct TestCase {
 
     pub skipped: Option<TestNegativeResult>,
 
+    /// Some frameworks mark disabled tests with `<disabled>` element, others with `status="disabled"`
+    pub disabled: Option<TestNegativeResult>,
+
     #[serde(rename = "error", default)]
     pub errors: Vec<TestNegativeResult>,
 
     #[serde(rename = "failure", default)]
     pub failures: Vec<TestNegativeResult>,
+
+    /// Failed runs of a test that passed when rerun, Maven Surefire writes them with `rerunFailingTestsCount`
+    #[serde(rename = "flakyFailure", default)]
+    pub flaky_failures: Vec<RerunResult>,
+    #[serde(rename = "flakyError", default)]
+    pub flaky_errors: Vec<RerunResult>,
+
+    /// Failed reruns of a test, some emitters write them for tests that passed in the end too
+    #[serde(rename = "rerunFailure", default)]
+    pub rerun_failures: Vec<RerunResult>,
+    #[serde(rename = "rerunError", default)]
+    pub rerun_errors: Vec<RerunResult>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct TestNegativeResult {
     #[serde(rename = "type", default)]
     pub error_type: Option<String>,
@@ -79,3 +119,108 @@ pub struct TestNegativeResult {
     #[serde(rename = "$value")]
     pub body: Option<String>,
 }
+
+/// Failed run of a test that was rerun
+#[derive(Debug, Default, Deserialize)]
+pub struct RerunResult {
+    #[serde(rename = "type", default)]
+    pub error_type: Option<String>,
+    pub message: Option<String>,
+
+    #[serde(rename = "stackTrace")]
+    pub stack_trace: Option<String>,
+    #[serde(rename = "system-out")]
+    pub system_out: Option<String>,
+    #[serde(rename = "system-err")]
+    pub system_err: Option<String>,
+}
+
+/// Outcome of a test case, ordered by severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestOutcome {
+    Success,
+    Disabled,
+    Skipped,
+    Failure,
+    Error,
+}
+
+impl TestSuite {
+    /// Returns full name of the suite, or a placeholder if it has none.
+    pub fn display_name(&self) -> &str {
+        return self.name.as_deref().unwrap_or(UNNAMED_SUITE);
+    }
+
+    /// Returns name of the suite without namespace, or a placeholder if it has none.
+    ///
+    /// Arguments:
+    /// * `langs` - languages to strip namespace of, see `strip_namespace`.
+    pub fn short_name(&self, langs: &[Lang]) -> &str {
+        return self.name.as_deref().map(|name| strip_namespace(name, langs)).unwrap_or(UNNAMED_SUITE);
+    }
+
+    /// Returns number of disabled tests. Suites that don't declare it
+    /// get it counted from test cases, so totals match the testcase breakdown.
+    pub fn disabled_count(&self) -> u64 {
+        if let Some(disabled) = self.disabled {
+            return disabled;
+        }
+
+        return self.testcases.iter().filter(|test| test.outcome() == TestOutcome::Disabled).count() as u64;
+    }
+}
+
+impl TestCase {
+    /// Computes outcome of the test case. If there are several negative results, the most severe one wins.
+    pub fn outcome(&self) -> TestOutcome {
+        if !self.errors.is_empty() {
+            return TestOutcome::Error;
+        }
+
+        if !self.failures.is_empty() {
+            return TestOutcome::Failure;
+        }
+
+        let status = self.status.as_deref().map(str::trim).unwrap_or_default();
+        let skipped_status = SKIPPED_STATUSES.iter().any(|skipped| status.eq_ignore_ascii_case(skipped));
+        if self.skipped.is_some() || skipped_status {
+            return TestOutcome::Skipped;
+        }
+
+        let disabled_status = status.eq_ignore_ascii_case("disabled");
+        if self.disabled.is_some() || disabled_status {
+            return TestOutcome::Disabled;
+        }
+
+        return TestOutcome::Success;
+    }
+
+    /// Returns number of times the test was rerun, from either `retries` or `attempts` attribute.
+    /// Counts that aren't whole numbers are ignored.
+    pub fn retry_count(&self) -> Option<u64> {
+        let parse_count = |count: &Option<String>| count.as_deref().and_then(|count| count.trim().parse::<u64>().ok());
+        return parse_count(&self.retries).or_else(|| parse_count(&self.attempts).map(|attempts| attempts.saturating_sub(1)));
+    }
+
+    /// Returns failed runs of the test before the final one, flaky ones first.
+    pub fn rerun_results(&self) -> impl Iterator<Item = &RerunResult> {
+        return self.flaky_failures.iter()
+            .chain(&self.flaky_errors)
+            .chain(&self.rerun_failures)
+            .chain(&self.rerun_errors);
+    }
+
+    /// Checks whether the test passed in the end, but failed some of its runs before.
+    pub fn is_flaky(&self) -> bool {
+        return self.outcome() == TestOutcome::Success && self.rerun_results().next().is_some();
+    }
+
+    /// Returns the negative result that determined outcome of the test case, if any.
+    pub fn negative_result(&self) -> Option<&TestNegativeResult> {
+        return self.errors.first()
+            .or_else(|| self.failures.first())
+            .or(self.skipped.as_ref())
+            .or(self.disabled.as_ref());
+    }
+}
@@ -0,0 +1,690 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use failure::Error;
+use serde_derive::Deserialize;
+
+use junit2md::lang_specific::Lang;
+use junit2md::md::Flavor;
+use junit2md::model::TestOutcome;
+
+use crate::baseline::Baseline;
+use crate::totals::ReportTotals;
+
+/// Name of the config file with default options
+pub const CONFIG_FILE_NAME: &str = ".junit2md.toml";
+
+/// Environment variable that enables verbose output
+pub const VERBOSE_ENV_VAR: &str = "JUNIT2MD_VERBOSE";
+
+/// Options that control how the report is generated.
+/// Read from config file first, then overridden by command-line arguments and then passed down to report functions.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ReportOptions {
+    /// Verbose output (hostnames, properties, standard streams)
+    pub verbose: bool,
+
+    /// Show how long ago suites were started in verbose mode and in the started column
+    pub relative_time: bool,
+
+    /// Compute pass rate in aggregated report against tests that weren't skipped or disabled
+    pub exclude_skipped_from_rate: bool,
+
+    /// Treat skipped and disabled tests as not passing in the health badge, pass rate and exit code
+    pub skips_as_failures: bool,
+
+    /// Recompute counts of suites and the whole report from test cases, ignoring declared ones
+    pub trust_testcases: bool,
+
+    /// Add column with suite start time to aggregated report
+    pub show_timestamps: bool,
+
+    /// Show output of passed tests too
+    pub verbose_passing: bool,
+
+    /// Suppress non-fatal warnings on stderr
+    pub quiet: bool,
+
+    /// Width of the per-suite result bar in aggregated report, 0 disables it
+    pub bar_width: usize,
+
+    /// Columns of the totals table of aggregated report, in order. Depend on other options if not set
+    pub columns: Option<Vec<TotalsColumn>>,
+
+    /// Fixed width of all Markdown table columns, longer cells are shortened. Columns fit their content if not set
+    pub table_width: Option<usize>,
+
+    /// Custom title of the report, overrides the derived one
+    pub title: Option<String>,
+
+    /// Title of single suite reports read from a file, overrides the suite name but not `title`.
+    /// Only given on the command line
+    #[serde(skip)]
+    pub file_title: Option<String>,
+
+    /// Append test counts to the report title
+    pub header_counts: bool,
+
+    /// Format of input files
+    pub input_format: InputFormat,
+
+    /// Markdown dialect of the report
+    pub flavor: Flavor,
+
+    /// Wrap failure bodies and test output into collapsible sections
+    pub collapse: bool,
+
+    /// Add failure details section, linked from testcase breakdown
+    pub details: bool,
+
+    /// Show percentage and pass rate columns in totals tables
+    pub percent: bool,
+
+    /// Check reports for suspicious content and warn about it
+    pub validate: bool,
+
+    /// Show test status reported by the test framework in testcase breakdown
+    pub show_reported_status: bool,
+
+    /// Show how many times each test was rerun in testcase breakdown
+    pub show_retries: bool,
+
+    /// Add list of suites with their failure counts to aggregated report
+    pub toc: bool,
+
+    /// Add overall health indicator under the report title
+    pub health_badge: bool,
+
+    /// Add a sentence with counts of suites and test results under the aggregated report title
+    pub summary_line: bool,
+
+    /// Add a line with counts of passed, failed and skipped tests under the suite title
+    pub suite_status: bool,
+
+    /// Add a sparkline of test durations to single suite report
+    pub sparkline: bool,
+
+    /// Add anchors to rows of testcase breakdown
+    pub row_anchors: bool,
+
+    /// Show exception type next to the failure link in testcase breakdown
+    pub cause_type: bool,
+
+    /// Show test class name in testcase breakdown
+    pub show_classname: bool,
+
+    /// Remove ANSI escape sequences from failure details and test output
+    pub strip_ansi: bool,
+
+    /// Only keep this many last lines of failure bodies and test output
+    pub tail_output: Option<usize>,
+
+    /// Only show details of this many first failures
+    pub max_failures: Option<usize>,
+
+    /// Add a section listing skipped tests with skip reasons
+    pub list_skips: bool,
+
+    /// Add a section with failed runs of tests that passed when rerun, and their count to the totals
+    pub show_flaky: bool,
+
+    /// Add a section with test counts of each class to single suite report
+    pub by_class: bool,
+
+    /// Width of the longest run of characters without a break in test names of testcase breakdown, 0 disables breaking
+    pub soft_break_width: usize,
+
+    /// Symbols that mark test outcomes
+    pub symbols: StatusSymbols,
+
+    /// Summaries of collapsible sections with failure details and test output
+    pub labels: SectionLabels,
+
+    /// Regular expression test suite names must match to be reported
+    pub suite_filter: Option<String>,
+
+    /// Exact names of test suites to report, all suites are reported if empty
+    pub only_suite: Vec<String>,
+
+    /// Regular expression test case names must match to be reported, counts are recomputed from the matching ones
+    pub select_tests: Option<String>,
+
+    /// Which name of test case `select_tests` is matched against
+    pub select_tests_by: TestNameMatch,
+
+    /// Regular expressions matching parts of property values to hide in the report
+    pub mask_property: Vec<String>,
+
+    /// How to merge properties declared several times with the same name, all of them are listed if not set
+    pub dedupe_properties: Option<PropertyDedupe>,
+
+    /// RFC 3339 instant, suites started before it are not reported
+    pub since: Option<String>,
+
+    /// Order of suites in aggregated report
+    pub sort_suites: SuiteOrder,
+
+    /// Order of failure details of each suite
+    pub sort_failures: FailureOrder,
+
+    /// Minimal severity of negative test results to show details for
+    pub min_severity: TestOutcome,
+
+    /// Formats of the generated report, each one is rendered from the same parsed input
+    pub format: Vec<OutputFormat>,
+
+    /// Languages of the tested code, namespace of test and class names is removed by the first one that fits
+    pub lang: Vec<Lang>,
+
+    /// File to write the report to instead of stdout
+    pub output: Option<String>,
+
+    /// Summary of the previous run to show changes against, only given on the command line
+    #[serde(skip)]
+    pub baseline: Option<Baseline>,
+
+    /// Line endings of the written report
+    pub line_ending: LineEnding,
+
+    /// Markdown file to put the report into, in place of `{{report}}` placeholder
+    pub template: Option<String>,
+
+    /// Append a line with junit2md version and number of input files to the report
+    pub footer: bool,
+
+    /// Append original content of input files to the report
+    pub embed_source: bool,
+
+    /// Add the command line the report was generated with to the footer
+    pub footer_command: bool,
+
+    /// Only print the totals table
+    pub compact: bool,
+
+    /// Only print a list of failed tests with their messages, instead of Markdown report
+    pub failures_snippet: bool,
+
+    /// Directory to write a separate report for each suite to
+    pub split_output: Option<String>,
+
+    /// File to write JSON manifest of the files written with `split_output` to
+    pub split_manifest: Option<String>,
+
+    /// Print time spent in each phase of the conversion
+    pub timing: bool,
+
+    /// Mapping of report outcomes to exit codes, see `ExitPolicy::parse`
+    pub exit_policy: Option<String>,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        ReportOptions {
+            verbose: false,
+            relative_time: false,
+            exclude_skipped_from_rate: false,
+            skips_as_failures: false,
+            trust_testcases: false,
+            show_timestamps: false,
+            verbose_passing: false,
+            quiet: false,
+            bar_width: 20,
+            table_width: None,
+            columns: None,
+            title: None,
+            file_title: None,
+            header_counts: true,
+            input_format: InputFormat::Junit,
+            flavor: Flavor::Github,
+            collapse: true,
+            details: true,
+            percent: true,
+            validate: false,
+            show_reported_status: false,
+            show_retries: false,
+            toc: false,
+            health_badge: false,
+            summary_line: true,
+            suite_status: false,
+            sparkline: false,
+            row_anchors: false,
+            cause_type: false,
+            show_classname: false,
+            strip_ansi: false,
+            tail_output: None,
+            max_failures: None,
+            list_skips: false,
+            show_flaky: false,
+            by_class: false,
+            soft_break_width: 0,
+            symbols: StatusSymbols::default(),
+            labels: SectionLabels::default(),
+            suite_filter: None,
+            only_suite: vec![],
+            select_tests: None,
+            select_tests_by: TestNameMatch::Full,
+            mask_property: vec![],
+            dedupe_properties: None,
+            since: None,
+            sort_suites: SuiteOrder::Input,
+            sort_failures: FailureOrder::Input,
+            min_severity: TestOutcome::Skipped,
+            format: vec![OutputFormat::Markdown],
+            lang: vec![Lang::Java],
+            output: None,
+            baseline: None,
+            line_ending: LineEnding::Lf,
+            template: None,
+            footer: false,
+            embed_source: false,
+            footer_command: false,
+            compact: false,
+            failures_snippet: false,
+            split_output: None,
+            split_manifest: None,
+            timing: false,
+            exit_policy: None,
+        }
+    }
+}
+
+impl ReportOptions {
+    /// Returns columns of the totals table of aggregated report: the ones set with `--columns`,
+    /// or the default set, adjusted by `--no-percent`, `--show-timestamps` and `--bar-width`.
+    pub fn totals_columns(&self) -> Vec<TotalsColumn> {
+        if let Some(columns) = &self.columns {
+            return columns.clone();
+        }
+
+        let mut columns = vec![TotalsColumn::Name, TotalsColumn::Time];
+        if self.show_timestamps {
+            columns.push(TotalsColumn::Started);
+        }
+        columns.extend([TotalsColumn::Success, TotalsColumn::Skipped, TotalsColumn::Disabled, TotalsColumn::Failures, TotalsColumn::Total]);
+        if self.percent {
+            columns.extend([TotalsColumn::Percent, TotalsColumn::PassRate]);
+        }
+        if self.bar_width > 0 {
+            columns.push(TotalsColumn::Results);
+        }
+
+        return columns;
+    }
+
+    /// Reads options from the TOML config file.
+    /// Options absent in the file get their default values.
+    ///
+    /// Arguments:
+    /// * `path` - path to the config file.
+    pub fn from_config_file(path: &Path) -> Result<ReportOptions, Error> {
+        let content = fs::read_to_string(path)?;
+        let opts = toml::from_str(&content)?;
+        return Ok(opts);
+    }
+
+    /// Overrides options with values set in environment variables.
+    /// Only `1` and `true` (in any case) enable a flag, anything else leaves it as is.
+    pub fn apply_env_vars(&mut self) {
+        if let Some(value) = env::var_os(VERBOSE_ENV_VAR) {
+            let value = value.to_string_lossy();
+            if value == "1" || value.eq_ignore_ascii_case("true") {
+                self.verbose = true;
+            }
+        }
+    }
+}
+
+/// Symbols marking outcomes of tests in the report
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatusSymbols {
+    pub pass: String,
+    pub fail: String,
+    pub error: String,
+    pub skip: String,
+    pub disabled: String,
+}
+
+impl Default for StatusSymbols {
+    fn default() -> Self {
+        StatusSymbols {
+            pass: "✓".to_owned(),
+            fail: "✗".to_owned(),
+            error: "‼".to_owned(),
+            skip: "✂".to_owned(),
+            disabled: "⊘".to_owned(),
+        }
+    }
+}
+
+impl StatusSymbols {
+    /// Checks that no symbol is empty, tests with it would have a blank status.
+    /// Symbols come from the config file and the command line, so this is done once they are merged.
+    pub fn validate(&self) -> Result<(), String> {
+        let symbols = [
+            ("pass", &self.pass),
+            ("fail", &self.fail),
+            ("error", &self.error),
+            ("skip", &self.skip),
+            ("disabled", &self.disabled),
+        ];
+        match symbols.iter().find(|(_, symbol)| symbol.trim().is_empty()) {
+            Some((name, _)) => return Err(format!("{} symbol must not be empty", name)),
+            None => return Ok(()),
+        }
+    }
+
+    /// Returns symbol marking the given test outcome
+    pub fn for_outcome(&self, outcome: TestOutcome) -> &str {
+        match outcome {
+            TestOutcome::Success => return &self.pass,
+            TestOutcome::Failure => return &self.fail,
+            TestOutcome::Error => return &self.error,
+            TestOutcome::Skipped => return &self.skip,
+            TestOutcome::Disabled => return &self.disabled,
+        }
+    }
+}
+
+/// Summaries of collapsible sections in the report.
+/// Empty labels fall back to the default ones.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SectionLabels {
+    pub details: String,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl SectionLabels {
+    /// Returns summary of the section with failure details
+    pub fn details(&self) -> &str {
+        return non_empty_or(&self.details, "Click to show details");
+    }
+
+    /// Returns summary of the section with test stdout
+    pub fn stdout(&self) -> &str {
+        return non_empty_or(&self.stdout, "Click to show test stdout");
+    }
+
+    /// Returns summary of the section with test stderr
+    pub fn stderr(&self) -> &str {
+        return non_empty_or(&self.stderr, "Click to show test stderr");
+    }
+}
+
+/// Returns the label unless it's blank, the default one otherwise.
+fn non_empty_or<'a>(label: &'a str, default: &'a str) -> &'a str {
+    if label.trim().is_empty() {
+        return default;
+    }
+    return label;
+}
+
+/// Supported formats of input test reports
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputFormat {
+    /// JUnit XML, either single suite or aggregated
+    #[default]
+    Junit,
+    /// xUnit.net v2 XML
+    Xunit,
+    /// TestNG `testng-results.xml`
+    Testng,
+}
+
+/// Order of suites in aggregated report.
+/// Ties are broken by suite name, so the order doesn't depend on the order of input files.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuiteOrder {
+    /// As they come in input files
+    #[default]
+    Input,
+    /// By full suite name
+    Name,
+    /// Slowest suites first
+    Time,
+    /// Suites with most failed tests first
+    Failures,
+}
+
+/// Order of failure details of a suite, see `--sort-failures`
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailureOrder {
+    /// As test cases come in the suite
+    #[default]
+    Input,
+    /// By class name without namespace, then by test name
+    Class,
+    /// By test name
+    Name,
+    /// Slowest tests first
+    Time,
+}
+
+/// Merging of properties with the same name, see `--dedupe-properties`
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PropertyDedupe {
+    /// Only the last value is kept, the same way later declarations override earlier ones
+    Last,
+    /// All values are listed in a single entry
+    Group,
+}
+
+/// Name of test case matched by `--select-tests`
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestNameMatch {
+    /// Class name and test name joined by a dot, before namespace is removed
+    #[default]
+    Full,
+    /// Test name without namespace, as shown in the report
+    Short,
+}
+
+/// Supported formats of the generated report
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Markdown document
+    #[default]
+    Markdown,
+    /// Short colored summary for the terminal
+    Term,
+    /// Plain text with aligned columns, for logs and emails
+    Text,
+    /// AsciiDoc document with the same sections as the Markdown one
+    Asciidoc,
+    /// HTML fragment with its own style, for embedding into web pages
+    Html,
+    /// Summary with counts of each suite, can be used as baseline of the next run
+    Json,
+}
+
+impl OutputFormat {
+    /// Returns extension of report files in this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Markdown => return "md",
+            OutputFormat::Term => return "term.txt",
+            OutputFormat::Text => return "txt",
+            OutputFormat::Asciidoc => return "adoc",
+            OutputFormat::Html => return "html",
+            OutputFormat::Json => return "json",
+        }
+    }
+}
+
+/// Line endings of the written report. Reports are always rendered with `\n`,
+/// they're converted right before writing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// Unix-style `\n`
+    #[default]
+    Lf,
+    /// Windows-style `\r\n`
+    Crlf,
+}
+
+/// Column of the totals table of aggregated report, see `--columns`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TotalsColumn {
+    Name,
+    Time,
+    /// Start time of the suite
+    Started,
+    Success,
+    Skipped,
+    Disabled,
+    /// Failed tests, without ones with errors if there's also an errors column
+    Failures,
+    Errors,
+    Total,
+    /// Share of all tests of the report
+    Percent,
+    PassRate,
+    /// Bar chart of results
+    Results,
+}
+
+impl TotalsColumn {
+    /// Keys of all columns, as they're given in `--columns`
+    pub const KEYS: &'static [&'static str] = &[
+        "name", "time", "started", "success", "skipped", "disabled", "failures", "errors", "total", "percent", "pass_rate", "results",
+    ];
+
+    /// Parses comma-separated list of column keys, e.g. `name,success,failures,total`.
+    ///
+    /// Arguments:
+    /// * `spec` - list of column keys.
+    pub fn parse_list(spec: &str) -> Result<Vec<TotalsColumn>, String> {
+        return spec.split(',')
+            .map(|key| match key.trim() {
+                "name" => Ok(TotalsColumn::Name),
+                "time" => Ok(TotalsColumn::Time),
+                "started" => Ok(TotalsColumn::Started),
+                "success" => Ok(TotalsColumn::Success),
+                "skipped" => Ok(TotalsColumn::Skipped),
+                "disabled" => Ok(TotalsColumn::Disabled),
+                "failures" => Ok(TotalsColumn::Failures),
+                "errors" => Ok(TotalsColumn::Errors),
+                "total" => Ok(TotalsColumn::Total),
+                "percent" => Ok(TotalsColumn::Percent),
+                "pass_rate" => Ok(TotalsColumn::PassRate),
+                "results" => Ok(TotalsColumn::Results),
+                key => Err(format!("unknown column '{}', expected one of {}", key, TotalsColumn::KEYS.join(", "))),
+            })
+            .collect();
+    }
+
+    /// Returns header of the column.
+    pub fn header(&self) -> &'static str {
+        match self {
+            TotalsColumn::Name => return "Suite name",
+            TotalsColumn::Time => return "Time taken, s",
+            TotalsColumn::Started => return "Started",
+            TotalsColumn::Success => return "Success",
+            TotalsColumn::Skipped => return "Skipped",
+            TotalsColumn::Disabled => return "Disabled",
+            TotalsColumn::Failures => return "Failures",
+            TotalsColumn::Errors => return "Errors",
+            TotalsColumn::Total => return "Total",
+            TotalsColumn::Percent => return "% of all tests",
+            TotalsColumn::PassRate => return "Pass rate",
+            TotalsColumn::Results => return "Results",
+        }
+    }
+}
+
+/// Exit codes for each outcome of the whole run
+#[derive(Debug)]
+pub struct ExitPolicy {
+    pub success: i32,
+    pub skipped: i32,
+    pub failed: i32,
+    pub parse_error: i32,
+}
+
+impl Default for ExitPolicy {
+    fn default() -> Self {
+        ExitPolicy {
+            success: 0,
+            skipped: 0,
+            failed: 2,
+            parse_error: 3,
+        }
+    }
+}
+
+impl ExitPolicy {
+    /// Parses exit policy from comma-separated `outcome=code` pairs, e.g. `failed=2,skipped=3`.
+    /// Outcomes not mentioned get default codes, `default` alone means all defaults.
+    ///
+    /// Arguments:
+    /// * `spec` - policy specification.
+    pub fn parse(spec: &str) -> Result<ExitPolicy, String> {
+        let mut policy = ExitPolicy::default();
+        if spec.trim() == "default" {
+            return Ok(policy);
+        }
+
+        for pair in spec.split(',') {
+            let (outcome, code) = match pair.split_once('=') {
+                Some((outcome, code)) => (outcome.trim(), code.trim()),
+                None => return Err(format!("expected outcome=code, got '{}'", pair)),
+            };
+
+            let code = code.parse().map_err(|_| format!("invalid exit code '{}'", code))?;
+            match outcome {
+                "success" => policy.success = code,
+                "skipped" => policy.skipped = code,
+                "failed" => policy.failed = code,
+                "parse-error" => policy.parse_error = code,
+                _ => return Err(format!("unknown outcome '{}'", outcome)),
+            }
+        }
+
+        return Ok(policy);
+    }
+
+    /// Picks exit code for the run. Parse errors are the most severe outcome, then failures, then skips.
+    ///
+    /// Arguments:
+    /// * `totals` - totals of the whole report.
+    /// * `parse_errors` - number of input files that couldn't be parsed.
+    pub fn exit_code(&self, totals: &ReportTotals, parse_errors: usize) -> i32 {
+        if parse_errors > 0 {
+            return self.parse_error;
+        }
+
+        if totals.failed > 0 {
+            return self.failed;
+        }
+
+        if totals.skipped + totals.disabled > 0 {
+            return self.skipped;
+        }
+
+        return self.success;
+    }
+}
+
+/// Looks for the config file in the current directory, then in the home directory.
+/// Returns path to the first one found, if any.
+pub fn find_config_file() -> Option<PathBuf> {
+    let home_dir = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"));
+    let candidates = vec![Some(PathBuf::from(".")), home_dir.map(PathBuf::from)];
+
+    return candidates.into_iter()
+        .flatten()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|path| path.is_file());
+}
@@ -0,0 +1,441 @@
+use std::cmp;
+
+use junit2md::md::*;
+use junit2md::model::*;
+
+use crate::{failure_anchor, format_cause_type, format_pass_rate, format_retries, format_suite_start, suite_failure_anchors};
+use crate::baseline::CountChanges;
+use crate::options::{ReportOptions, TotalsColumn};
+use crate::totals::*;
+
+/// Emits sections of the report in some document format.
+/// The walk over suites and tests is done by the caller, see `render_report`,
+/// which calls these in the order sections should appear in the document.
+pub trait ReportRenderer {
+    /// Adds report title.
+    fn header(&mut self, title: &str);
+
+    /// Adds a line with overall health of the report.
+    fn health_badge(&mut self, badge: &str);
+
+    /// Adds a sentence summarizing results of the whole report.
+    fn summary_line(&mut self, summary: &str);
+
+    /// Adds a line with counts of test results of the suite.
+    fn suite_status(&mut self, status: &str);
+
+    /// Adds a note that there's nothing to report.
+    fn no_suites(&mut self);
+
+    /// Adds start time, host and properties of the suite.
+    fn suite_properties(&mut self, suite: &TestSuite);
+
+    /// Adds labeled list of properties of the aggregated report, declared on its root or shared by all suites.
+    fn report_properties(&mut self, label: &str, properties: &[&TestProperty]);
+
+    /// Adds list of suites with their failure counts.
+    fn table_of_contents(&mut self, entries: &[TocEntry]);
+
+    /// Adds secondary section header.
+    fn section(&mut self, title: &str);
+
+    /// Adds totals table of a single suite.
+    fn suite_totals(&mut self, suite: &TestSuite);
+
+    /// Adds totals table of several suites, with a total row.
+    fn totals(&mut self, suites: &[TestSuite], totals: &ReportTotals);
+
+    /// Adds lists of suites that appeared or disappeared since the baseline, see `--baseline`.
+    fn baseline_suites(&mut self, added: &[&str], removed: &[&str]);
+
+    /// Adds table of test counts of each class, see `count_by_class`.
+    fn class_summary(&mut self, classes: &[ClassCounts]);
+
+    /// Adds breakdown table with a row for each test.
+    fn testcase_summary(&mut self, rows: &[TestcaseRow]);
+
+    /// Adds a line with sparkline of test durations.
+    fn durations(&mut self, sparkline: &str);
+
+    /// Adds stdout and stderr of a passed test.
+    fn test_output(&mut self, test: &TestCase);
+
+    /// Adds details of a failed test, under an anchor the breakdown table links to.
+    fn failure_detail(&mut self, failure: &FailureDetail);
+
+    /// Adds failed runs of a test that passed when rerun, see `--show-flaky`.
+    fn flaky_test(&mut self, test: &TestCase);
+
+    /// Adds a note on failures whose details are not shown, see `--max-failures`.
+    fn omitted_failures(&mut self, count: u64);
+
+    /// Adds table of skipped tests and reasons they were skipped.
+    fn skipped_list(&mut self, skipped: &[SkippedTest]);
+
+    /// Adds original content of an input file in a collapsible block labeled with its path.
+    fn input_source(&mut self, path: &str, source: &str);
+
+    /// Adds a line noting how the report was generated.
+    fn footer(&mut self, footer: &str);
+
+    /// Returns the rendered document.
+    fn finish(self) -> String;
+}
+
+/// Row of the testcase breakdown table
+pub struct TestcaseRow<'a> {
+    pub test: &'a TestCase,
+    /// Id of the anchor for linking to the row from outside, if rows are anchored
+    pub anchor: Option<String>,
+    /// Index of failure details of the test, if it has them
+    pub failure: Option<usize>,
+    /// Whether failure details of the test are shown, so the row can link to them
+    pub failure_shown: bool,
+}
+
+/// Failed test reported in failure details
+pub struct FailureDetail<'a> {
+    /// Index of the failure across the whole document, the breakdown table refers to it
+    pub index: usize,
+    /// Name of the suite the test came from, if it should be shown
+    pub suite_name: Option<&'a str>,
+    /// Anchor the totals table links the suite to, set on the first failure of each suite
+    pub suite_anchor: Option<String>,
+    pub test: &'a TestCase,
+    /// Negative result to report, the most severe one of the test
+    pub result: &'a TestNegativeResult,
+}
+
+impl FailureDetail<'_> {
+    /// Returns label of the line with the reason of the failure and the message to show after it.
+    /// Skips are deliberate, bare `<skipped/>` is normal and has no message,
+    /// failures always have one, even if the report doesn't specify it.
+    pub fn reason(&self) -> (&'static str, Option<&str>) {
+        let message = self.result.message.as_deref();
+        match self.test.outcome() {
+            TestOutcome::Skipped => return ("Skipped", message),
+            TestOutcome::Disabled => return ("Disabled", message),
+            _ => return ("Fail reason", Some(message.unwrap_or("Not specified"))),
+        }
+    }
+}
+
+/// Entry of the table of contents
+pub struct TocEntry<'a> {
+    pub suite: &'a TestSuite,
+    /// Number of failed tests in the suite
+    pub failed: u64,
+    /// Index of the first failure of the suite in failure details, if there's one to link to
+    pub first_failure: Option<usize>,
+}
+
+/// Skipped test along with the reason it was skipped
+pub struct SkippedTest<'a> {
+    pub test: &'a TestCase,
+    pub reason: &'a str,
+}
+
+/// Counts of test results of a single class, see `count_by_class`
+pub struct ClassCounts<'a> {
+    /// Full class name, `None` for tests without one
+    pub classname: Option<&'a str>,
+    pub tests: u64,
+    pub passed: u64,
+    /// Failed tests, including ones with errors
+    pub failed: u64,
+    /// Skipped tests, including disabled ones
+    pub skipped: u64,
+}
+
+/// Counts results of tests by their class, classes with most tests go first.
+/// Each test is counted once by its outcome, so counts add up to the breakdown by testcases.
+///
+/// Arguments:
+/// * `tests` - testcases of the suite.
+pub fn count_by_class(tests: &[TestCase]) -> Vec<ClassCounts<'_>> {
+    let mut classes: Vec<ClassCounts> = vec![];
+    for test in tests {
+        let classname = test.classname.as_deref();
+        let index = match classes.iter().position(|class| class.classname == classname) {
+            Some(index) => index,
+            None => {
+                classes.push(ClassCounts { classname, tests: 0, passed: 0, failed: 0, skipped: 0 });
+                classes.len() - 1
+            }
+        };
+
+        let class = &mut classes[index];
+        class.tests += 1;
+        match test.outcome() {
+            TestOutcome::Success => class.passed += 1,
+            TestOutcome::Skipped | TestOutcome::Disabled => class.skipped += 1,
+            TestOutcome::Failure | TestOutcome::Error => class.failed += 1,
+        }
+    }
+    // stable sort keeps classes with the same count in report order
+    classes.sort_by_key(|class| cmp::Reverse(class.tests));
+
+    return classes;
+}
+
+/// Builds table of test counts by class: header and a row for each class.
+/// Tests without class are counted in the `(none)` row.
+///
+/// Arguments:
+/// * `classes` - counts of each class, see `count_by_class`.
+/// * `format_name` - formats class name for the output format.
+pub fn class_counts_rows(classes: &[ClassCounts], format_name: impl Fn(&str) -> String) -> Vec<Vec<String>> {
+    let mut rows = vec![vec![
+        "Class".to_owned(),
+        "Total".to_owned(),
+        "Passed".to_owned(),
+        "Failed".to_owned(),
+        "Skipped".to_owned(),
+    ]];
+    for class in classes {
+        rows.push(vec![
+            format_name(class.classname.unwrap_or("(none)")),
+            format_count(class.tests),
+            format_count(class.passed),
+            format_count(class.failed),
+            format_count(class.skipped),
+        ]);
+    }
+
+    return rows;
+}
+
+/// Formatting of testcase breakdown table cells that differs between output formats
+pub struct TestcaseCells {
+    /// Escapes user strings, such as time, status and cause type
+    pub escape: fn(&str) -> String,
+    /// Formats `[N]` reference to failure details, linked to their anchor if they're shown
+    pub failure_ref: fn(usize, Option<&str>) -> String,
+    /// Formats anchor for linking to the row from outside, it's put in front of the test name
+    pub anchor: fn(&str) -> String,
+}
+
+/// Builds testcase breakdown table: header and a row for each test.
+/// Optional columns are picked by report options, tests with failure details refer to them in the Cause column.
+///
+/// Arguments:
+/// * `rows` - rows of the table, one for each test.
+/// * `opts` - report options.
+/// * `cells` - formatting of the cells.
+/// * `format_name` - formats test and class names for the output format.
+pub fn testcase_rows(rows: &[TestcaseRow], opts: &ReportOptions, cells: &TestcaseCells, format_name: impl Fn(&str) -> String) -> Vec<Vec<String>> {
+    let mut header = vec!["Testcase name".to_owned()];
+    if opts.show_classname {
+        header.push("Class".to_owned());
+    }
+    header.push("Status".to_owned());
+    header.push("Time".to_owned());
+    if opts.details {
+        header.push("Cause".to_owned());
+    }
+    if opts.show_reported_status {
+        header.push("Status (reported)".to_owned());
+    }
+    if opts.show_retries {
+        header.push("Retries".to_owned());
+    }
+
+    let mut table = vec![header];
+    for TestcaseRow { test, anchor, failure, failure_shown } in rows {
+        let mut name = format_name(&test.name);
+        if let Some(anchor) = anchor {
+            name.insert_str(0, &(cells.anchor)(anchor));
+        }
+
+        let mut row = vec![name];
+        if opts.show_classname {
+            row.push(format_name(test.classname.as_deref().unwrap_or_default()));
+        }
+        row.push((cells.escape)(opts.symbols.for_outcome(test.outcome())));
+        row.push((cells.escape)(test.time.as_deref().unwrap_or_default()));
+        if opts.details {
+            let mut cause = String::new();
+            if let Some(fail_index) = *failure {
+                // link to failure details, unless they're cut off
+                let anchor = if *failure_shown { Some(failure_anchor(fail_index)) } else { None };
+                cause = (cells.failure_ref)(fail_index, anchor.as_deref());
+                if let Some(cause_type) = format_cause_type(test, opts) {
+                    cause.push(' ');
+                    cause.push_str(&(cells.escape)(&cause_type));
+                }
+            }
+            row.push(cause);
+        }
+        if opts.show_reported_status {
+            row.push((cells.escape)(test.status.as_deref().unwrap_or_default()));
+        }
+        if opts.show_retries {
+            row.push(format_retries(test));
+        }
+        table.push(row);
+    }
+
+    return table;
+}
+
+/// Prepares long text, such as failure body or test output, to be shown in the report:
+/// normalizes line endings, strips ANSI escape codes and keeps only the last lines if requested.
+///
+/// Arguments:
+/// * `code` - the text to prepare.
+/// * `opts` - report options.
+pub fn prepare_code(code: &str, opts: &ReportOptions) -> String {
+    let mut code = normalize_newlines(code);
+    if opts.strip_ansi {
+        code = strip_ansi_codes(&code);
+    }
+    if let Some(lines) = opts.tail_output {
+        code = tail_lines(&code, lines);
+    }
+
+    return code;
+}
+
+/// Formatting of totals table cells that differs between output formats
+pub struct TotalsCells {
+    /// Escapes user strings, such as suite name and time
+    pub escape: fn(&str) -> String,
+    /// Marker added to success count if the suite totals don't add up, see `mark_success`
+    pub marker: &'static str,
+    /// Emphasizes label of the summary row, `Success` or `Total`
+    pub emphasize: fn(&str) -> String,
+    /// Links already escaped text to the anchor in the same document
+    pub link: fn(&str, &str) -> String,
+}
+
+/// Formats count of successful tests, adding the marker if suite totals don't add up.
+///
+/// Arguments:
+/// * `success_tests` - count of successful tests, see `count_success`.
+/// * `consistent` - whether the suite totals add up.
+/// * `cells` - formatting of the cells, with the marker.
+pub fn mark_success(success_tests: u64, consistent: bool, cells: &TotalsCells) -> String {
+    if consistent {
+        return format_count(success_tests);
+    }
+
+    return format!("{} {}", format_count(success_tests), cells.marker);
+}
+
+/// Builds note explaining the inconsistent totals marker, listing suites whose totals don't add up.
+/// The output format adds the marker or footnote label to it.
+///
+/// Arguments:
+/// * `suites` - test suites whose totals don't add up.
+/// * `opts` - report options.
+/// * `cells` - formatting of the cells, suite names are escaped with it.
+pub fn inconsistent_totals_note(suites: &[&TestSuite], opts: &ReportOptions, cells: &TotalsCells) -> String {
+    let suite_names: Vec<String> = suites.iter()
+        .map(|suite| format!("{} ({})", (cells.escape)(suite.short_name(&opts.lang)), pluralize(suite.tests, "test")))
+        .collect();
+
+    return format!("Declared totals don't add up or don't match test cases in: {}", suite_names.join(", "));
+}
+
+/// Builds totals table of a single suite: header and a row for each kind of result.
+///
+/// Arguments:
+/// * `suite` - test suite to get counts from.
+/// * `opts` - report options.
+/// * `cells` - formatting of the cells.
+pub fn suite_totals_rows(suite: &TestSuite, opts: &ReportOptions, cells: &TotalsCells) -> Vec<Vec<String>> {
+    let totals = compute_suite_totals(suite);
+    let (success_tests, consistent) = count_success(suite);
+    let mut rows = vec![
+        vec!["Type".to_owned(), "Number of tests".to_owned(), "% of total".to_owned()],
+        vec!["Skipped".to_owned(), format_count(totals.skipped), format_percent(totals.skipped, suite.tests)],
+        vec!["Disabled".to_owned(), format_count(totals.disabled), format_percent(totals.disabled, suite.tests)],
+        vec!["Failed".to_owned(), format_count(totals.failed), format_percent(totals.failed, suite.tests)],
+        vec![(cells.emphasize)("Success"), mark_success(success_tests, consistent, cells), format_percent(success_tests, suite.tests)],
+    ];
+    if opts.show_flaky {
+        let flaky_tests = count_flaky(suite);
+        rows.push(vec!["Flaky".to_owned(), format_count(flaky_tests), format_percent(flaky_tests, suite.tests)]);
+    }
+    if !opts.percent {
+        for row in &mut rows {
+            row.truncate(2);
+        }
+    }
+
+    return rows;
+}
+
+/// Builds totals table of several suites: header, a row for each suite and a total row.
+/// Columns are picked by `ReportOptions::totals_columns`.
+///
+/// Arguments:
+/// * `suites` - test suites to get counts from.
+/// * `totals` - totals of the whole report.
+/// * `opts` - report options.
+/// * `cells` - formatting of the cells.
+pub fn totals_rows(suites: &[TestSuite], totals: &ReportTotals, opts: &ReportOptions, cells: &TotalsCells) -> Vec<Vec<String>> {
+    let columns = opts.totals_columns();
+    let anchors = suite_failure_anchors(suites, opts);
+    let mut rows = vec![columns.iter().map(|column| column.header().to_owned()).collect()];
+    for (suite, anchor) in suites.iter().zip(&anchors) {
+        let suite_totals = compute_suite_totals(suite);
+        let (success_tests, consistent) = count_success(suite);
+        let changes = CountChanges::new(&suite_totals, opts.baseline.as_ref().and_then(|baseline| baseline.suite(suite)));
+
+        let row = columns.iter()
+            .map(|column| match column {
+                TotalsColumn::Name => match anchor {
+                    Some(anchor) => (cells.link)(&(cells.escape)(suite.short_name(&opts.lang)), anchor),
+                    None => (cells.escape)(suite.short_name(&opts.lang)),
+                },
+                TotalsColumn::Time => (cells.escape)(suite.time.as_deref().unwrap_or_default()),
+                TotalsColumn::Started => suite.timestamp.as_deref().map(|timestamp| format_suite_start(timestamp, opts)).unwrap_or_default(),
+                TotalsColumn::Success => mark_success(success_tests, consistent, cells) + &changes.success,
+                column => count_cell(column, &columns, &suite_totals, &changes, totals, opts),
+            })
+            .collect();
+        rows.push(row);
+    }
+
+    let changes = CountChanges::new(totals, opts.baseline.as_ref().map(|baseline| &baseline.totals));
+    let total_row = columns.iter()
+        .map(|column| match column {
+            TotalsColumn::Name => (cells.emphasize)("Total"),
+            TotalsColumn::Time => (cells.escape)(totals.time.as_deref().unwrap_or("N/A")),
+            TotalsColumn::Started => String::new(),
+            TotalsColumn::Success => format_count(totals.success) + &changes.success,
+            column => count_cell(column, &columns, totals, &changes, totals, opts),
+        })
+        .collect();
+    rows.push(total_row);
+
+    return rows;
+}
+
+/// Formats cell of the totals table with counts, which are computed the same way for suites and the whole report.
+/// Failures don't include errors if errors have their own column.
+///
+/// Arguments:
+/// * `column` - column of the cell.
+/// * `columns` - all columns of the table.
+/// * `totals` - totals of the suite or the whole report.
+/// * `changes` - changes of the counts since the baseline.
+/// * `report_totals` - totals of the whole report.
+/// * `opts` - report options.
+fn count_cell(column: &TotalsColumn, columns: &[TotalsColumn], totals: &ReportTotals, changes: &CountChanges,
+              report_totals: &ReportTotals, opts: &ReportOptions) -> String {
+    match column {
+        TotalsColumn::Skipped => return format_count(totals.skipped) + &changes.skipped,
+        TotalsColumn::Disabled => return format_count(totals.disabled) + &changes.disabled,
+        TotalsColumn::Failures if columns.contains(&TotalsColumn::Errors) => return format_count(totals.failed - totals.errors) + &changes.failures,
+        TotalsColumn::Failures => return format_count(totals.failed) + &changes.failed,
+        TotalsColumn::Errors => return format_count(totals.errors) + &changes.errors,
+        TotalsColumn::Total => return format_count(totals.tests) + &changes.tests,
+        TotalsColumn::Percent => return format_percent(totals.tests, report_totals.tests),
+        TotalsColumn::PassRate => return format_pass_rate(totals, opts),
+        TotalsColumn::Results => return create_bar_chart(totals.success, totals.skipped + totals.disabled, totals.failed, opts.bar_width),
+        // differ between suite rows and the total row
+        TotalsColumn::Name | TotalsColumn::Time | TotalsColumn::Started | TotalsColumn::Success => return String::new(),
+    }
+}
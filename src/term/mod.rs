@@ -0,0 +1,83 @@
+use owo_colors::OwoColorize;
+
+use junit2md::lang_specific::*;
+use junit2md::model::*;
+
+use crate::options::StatusSymbols;
+use crate::totals::*;
+
+/// Renders short human-readable summary of test suites for the terminal.
+/// Each suite gets a line with its counts followed by the list of its failed tests.
+/// The total line is added only if there's more than one suite.
+///
+/// Arguments:
+/// * `suites` - test suites to summarize.
+/// * `totals` - totals of the whole report, see `compute_totals`.
+/// * `symbols` - symbols marking failed tests.
+/// * `langs` - languages to strip namespace of test names, see `strip_namespace`.
+/// * `colored` - whether to colorize the output with ANSI escape codes.
+pub fn suites_to_term(suites: &[TestSuite], totals: &ReportTotals, symbols: &StatusSymbols, langs: &[Lang], colored: bool) -> String {
+    let mut out = String::new();
+
+    for suite in suites {
+        let suite_totals = compute_suite_totals(suite);
+        out.push_str(&format!("{}: {}\n", suite.short_name(langs), format_counts(&suite_totals, colored)));
+
+        for test in &suite.testcases {
+            let outcome = test.outcome();
+            if outcome != TestOutcome::Error && outcome != TestOutcome::Failure {
+                continue;
+            }
+
+            let mark = symbols.for_outcome(outcome);
+            let line = format!("  {} {}", mark, strip_namespace(&test.name, langs));
+            out.push_str(&paint(&line, Color::Red, colored));
+            out.push('\n');
+        }
+    }
+
+    if suites.len() > 1 {
+        out.push_str(&format!("Total: {}\n", format_counts(totals, colored)));
+    }
+
+    return out;
+}
+
+/// Terminal colors used in the summary
+enum Color {
+    Green,
+    Red,
+    Yellow,
+}
+
+/// Formats "X passed, Y failed, Z skipped" line, coloring non-zero counts.
+fn format_counts(totals: &ReportTotals, colored: bool) -> String {
+    let skipped = totals.skipped + totals.disabled;
+    let mut counts = vec![];
+    counts.push(paint_nonzero(&format!("{} passed", totals.success), totals.success, Color::Green, colored));
+    counts.push(paint_nonzero(&format!("{} failed", totals.failed), totals.failed, Color::Red, colored));
+    counts.push(paint_nonzero(&format!("{} skipped", skipped), skipped, Color::Yellow, colored));
+    return counts.join(", ");
+}
+
+/// Colors the text only if `count` is non-zero, zero counts are not interesting.
+fn paint_nonzero(text: &str, count: u64, color: Color, colored: bool) -> String {
+    if count == 0 {
+        return text.to_owned();
+    }
+
+    return paint(text, color, colored);
+}
+
+/// Colors the text with ANSI escape codes, if coloring is enabled.
+fn paint(text: &str, color: Color, colored: bool) -> String {
+    if !colored {
+        return text.to_owned();
+    }
+
+    match color {
+        Color::Green => return text.green().to_string(),
+        Color::Red => return text.red().to_string(),
+        Color::Yellow => return text.yellow().to_string(),
+    }
+}
@@ -0,0 +1,150 @@
+use serde_derive::Deserialize;
+
+use crate::model::*;
+
+/// Root of TestNG `testng-results.xml` report
+#[derive(Debug, Deserialize)]
+pub struct TestngResults {
+    #[serde(rename = "suite", default)]
+    pub suites: Vec<TestngSuite>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TestngSuite {
+    pub name: String,
+
+    #[serde(rename = "started-at")]
+    pub started_at: Option<String>,
+
+    /// Tests of the suite, as declared in `testng.xml`
+    #[serde(rename = "test", default)]
+    pub tests: Vec<TestngTest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TestngTest {
+    pub name: String,
+
+    #[serde(rename = "duration-ms")]
+    pub duration_ms: Option<u64>,
+    #[serde(rename = "started-at")]
+    pub started_at: Option<String>,
+
+    #[serde(rename = "class", default)]
+    pub classes: Vec<TestngClass>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TestngClass {
+    pub name: String,
+
+    #[serde(rename = "test-method", default)]
+    pub methods: Vec<TestngMethod>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TestngMethod {
+    pub name: String,
+
+    /// One of `PASS`, `FAIL` or `SKIP`
+    pub status: String,
+
+    #[serde(rename = "duration-ms")]
+    pub duration_ms: Option<u64>,
+
+    /// Set to `true` for `@BeforeXXX` and `@AfterXXX` methods
+    #[serde(rename = "is-config")]
+    pub is_config: Option<bool>,
+
+    pub exception: Option<TestngException>,
+
+    #[serde(rename = "reporter-output")]
+    pub reporter_output: Option<TestngReporterOutput>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TestngException {
+    pub class: Option<String>,
+    pub message: Option<String>,
+
+    #[serde(rename = "full-stacktrace")]
+    pub full_stacktrace: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TestngReporterOutput {
+    #[serde(rename = "line", default)]
+    pub lines: Vec<String>,
+}
+
+impl TestngResults {
+    /// Converts TestNG report into JUnit test suites, consuming it.
+    /// Each `<test>` of each suite becomes a separate test suite, test methods of all its classes become test cases.
+    /// Configuration methods are only reported if they didn't pass, as they are not tests by themselves.
+    pub fn into_testsuites(self) -> Vec<TestSuite> {
+        let mut suites = vec![];
+        for suite in self.suites {
+            let started_at = suite.started_at;
+            for test in suite.tests {
+                let testcases: Vec<TestCase> = test.classes.into_iter()
+                    .flat_map(|class| {
+                        let classname = class.name;
+                        class.methods.into_iter()
+                            .filter(|method| method.is_config != Some(true) || method.status != "PASS")
+                            .map(move |method| method.into_testcase(&classname))
+                    })
+                    .collect();
+
+                suites.push(TestSuite {
+                    name: Some(test.name),
+                    tests: testcases.len() as u64,
+                    package: Some(suite.name.clone()),
+                    failures: Some(testcases.iter().filter(|test| test.outcome() == TestOutcome::Failure).count() as u64),
+                    skipped: Some(testcases.iter().filter(|test| test.outcome() == TestOutcome::Skipped).count() as u64),
+                    time: test.duration_ms.map(format_millis),
+                    timestamp: test.started_at.or_else(|| started_at.clone()),
+                    testcases,
+                    ..Default::default()
+                });
+            }
+        }
+        return suites;
+    }
+}
+
+impl TestngMethod {
+    /// Converts TestNG test method into JUnit test case, consuming it.
+    ///
+    /// Arguments:
+    /// * `classname` - name of the class the method belongs to.
+    pub fn into_testcase(self, classname: &str) -> TestCase {
+        let mut testcase = TestCase {
+            name: self.name,
+            time: self.duration_ms.map(format_millis),
+            classname: Some(classname.to_owned()),
+            status: Some(self.status.clone()),
+            system_out: self.reporter_output.map(|output| output.lines.join("\n")).filter(|output| !output.is_empty()),
+            ..Default::default()
+        };
+
+        let exception = self.exception.unwrap_or_default();
+        let result = TestNegativeResult {
+            error_type: exception.class,
+            message: exception.message.map(|message| message.trim().to_owned()),
+            body: exception.full_stacktrace,
+        };
+
+        match self.status.as_str() {
+            "FAIL" => testcase.failures.push(result),
+            "SKIP" => testcase.skipped = Some(result),
+            _ => {}
+        }
+
+        return testcase;
+    }
+}
+
+/// Formats duration in milliseconds as seconds, the way JUnit reports do.
+fn format_millis(millis: u64) -> String {
+    return format!("{:.3}", millis as f64 / 1000.0);
+}
@@ -0,0 +1,314 @@
+use std::cmp;
+
+use junit2md::lang_specific::*;
+use junit2md::md::*;
+use junit2md::model::*;
+
+use crate::format_suite_start;
+use crate::options::*;
+use crate::render::*;
+use crate::totals::*;
+
+/// Formatting of totals table cells in plain text
+const TEXT_TOTALS_CELLS: TotalsCells = TotalsCells {
+    escape: str::to_owned,
+    marker: "⚠",
+    emphasize: str::to_owned,
+    link: |text, _| text.to_owned(),
+};
+
+/// Formatting of testcase breakdown table cells in plain text, anchors can't be linked to
+const TEXT_TESTCASE_CELLS: TestcaseCells = TestcaseCells {
+    escape: str::to_owned,
+    failure_ref: |fail_index, _| format!("[{}]", fail_index),
+    anchor: |_| String::new(),
+};
+
+/// Renders the report as plain text, with the same sections as the Markdown report.
+/// Tables have no borders and failure details are indented blocks, numbered to match
+/// `[N]` references in the breakdown table.
+pub struct TextRenderer<'a> {
+    text: String,
+    opts: &'a ReportOptions,
+}
+
+impl<'a> TextRenderer<'a> {
+    pub fn new(opts: &'a ReportOptions) -> Self {
+        return TextRenderer { text: String::new(), opts };
+    }
+}
+
+impl ReportRenderer for TextRenderer<'_> {
+    fn header(&mut self, title: &str) {
+        self.text.push_str(&format!("{}\n\n", title));
+    }
+
+    fn health_badge(&mut self, badge: &str) {
+        self.text.push_str(&format!("{}\n\n", badge));
+    }
+
+    fn summary_line(&mut self, summary: &str) {
+        self.text.push_str(&format!("{}\n\n", summary));
+    }
+
+    fn suite_status(&mut self, status: &str) {
+        self.text.push_str(&format!("{}\n\n", status));
+    }
+
+    fn no_suites(&mut self) {
+        self.text.push_str("No test suites found\n\n");
+    }
+
+    fn suite_properties(&mut self, suite: &TestSuite) {
+        if let (Some(timestamp), Some(hostname), Some(time)) = (&suite.timestamp, &suite.hostname, &suite.time) {
+            self.text.push_str(&format!("Testset was started on host {} at {} and took {} seconds to finish.\n\n",
+                hostname, format_suite_start(timestamp, self.opts), time));
+        }
+
+        if let Some(properties) = &suite.properties {
+            add_properties_list(&mut self.text, "Properties:", &properties.properties.iter().collect::<Vec<_>>(), self.opts);
+        }
+    }
+
+    fn report_properties(&mut self, label: &str, properties: &[&TestProperty]) {
+        add_properties_list(&mut self.text, label, properties, self.opts);
+    }
+
+    fn table_of_contents(&mut self, entries: &[TocEntry]) {
+        for entry in entries {
+            let name = entry.suite.short_name(&self.opts.lang);
+            match (entry.failed, entry.first_failure) {
+                (0, _) => self.text.push_str(&format!("* {} (no failures)\n", name)),
+                (failed, Some(fail_index)) => self.text.push_str(&format!("* {} ({}, see [{}])\n", name, pluralize(failed, "failure"), fail_index)),
+                (failed, None) => self.text.push_str(&format!("* {} ({})\n", name, pluralize(failed, "failure"))),
+            }
+        }
+        self.text.push('\n');
+    }
+
+    fn section(&mut self, title: &str) {
+        self.text.push_str(&format!("{}:\n\n", title));
+    }
+
+    fn suite_totals(&mut self, suite: &TestSuite) {
+        create_text_table(&mut self.text, suite_totals_rows(suite, self.opts, &TEXT_TOTALS_CELLS));
+
+        if !count_success(suite).1 {
+            add_inconsistent_totals_note(&mut self.text, &[suite], self.opts);
+        }
+    }
+
+    fn totals(&mut self, suites: &[TestSuite], totals: &ReportTotals) {
+        create_text_table(&mut self.text, totals_rows(suites, totals, self.opts, &TEXT_TOTALS_CELLS));
+
+        let inconsistent: Vec<&TestSuite> = suites.iter().filter(|suite| !count_success(suite).1).collect();
+        if !inconsistent.is_empty() {
+            add_inconsistent_totals_note(&mut self.text, &inconsistent, self.opts);
+        }
+    }
+
+    fn baseline_suites(&mut self, added: &[&str], removed: &[&str]) {
+        if !added.is_empty() {
+            self.text.push_str(&format!("New suites since baseline: {}\n", added.join(", ")));
+        }
+        if !removed.is_empty() {
+            self.text.push_str(&format!("Removed suites since baseline: {}\n", removed.join(", ")));
+        }
+        self.text.push('\n');
+    }
+
+    fn class_summary(&mut self, classes: &[ClassCounts]) {
+        let rows = class_counts_rows(classes, |name| strip_namespace(name, &self.opts.lang).to_owned());
+        create_text_table(&mut self.text, rows);
+    }
+
+    fn testcase_summary(&mut self, rows: &[TestcaseRow]) {
+        let rows = testcase_rows(rows, self.opts, &TEXT_TESTCASE_CELLS, |name| strip_namespace(name, &self.opts.lang).to_owned());
+        create_text_table(&mut self.text, rows);
+    }
+
+    fn durations(&mut self, sparkline: &str) {
+        self.text.push_str(&format!("Test durations: {}\n\n", sparkline));
+    }
+
+    fn test_output(&mut self, test: &TestCase) {
+        self.text.push_str(&format!("{}\n", test.name));
+
+        if let Some(out) = &test.system_out {
+            add_indented_block(&mut self.text, "Test stdout", out, self.opts);
+        }
+
+        if let Some(err) = &test.system_err {
+            add_indented_block(&mut self.text, "Test stderr", err, self.opts);
+        }
+        self.text.push('\n');
+    }
+
+    fn failure_detail(&mut self, failure: &FailureDetail) {
+        add_failure_detail(&mut self.text, failure, self.opts);
+    }
+
+    fn flaky_test(&mut self, test: &TestCase) {
+        self.text.push_str(&format!("{}\n", test.name));
+        for (index, rerun) in test.rerun_results().enumerate() {
+            let message = rerun.message.as_deref().unwrap_or("Not specified");
+            self.text.push_str(&format!("    Failed run {}: {}\n", index + 1, message));
+            if let Some(stack_trace) = &rerun.stack_trace {
+                add_indented_block(&mut self.text, "Details", stack_trace, self.opts);
+            }
+        }
+        self.text.push('\n');
+    }
+
+    fn omitted_failures(&mut self, count: u64) {
+        self.text.push_str(&format!("... and {} (not shown)\n\n", pluralize(count, "more failure")));
+    }
+
+    fn skipped_list(&mut self, skipped: &[SkippedTest]) {
+        let mut table = vec![vec!["Testcase name".to_owned(), "Reason".to_owned()]];
+        for SkippedTest { test, reason } in skipped {
+            table.push(vec![strip_namespace(&test.name, &self.opts.lang).to_owned(), reason.to_string()]);
+        }
+
+        create_text_table(&mut self.text, table);
+    }
+
+    fn input_source(&mut self, path: &str, source: &str) {
+        self.text.push_str(&format!("{}\n", path));
+        for line in normalize_newlines(source).trim_end().lines() {
+            self.text.push_str(format!("    {}", line).trim_end());
+            self.text.push('\n');
+        }
+        self.text.push('\n');
+    }
+
+    fn footer(&mut self, footer: &str) {
+        self.text.push_str(&format!("{}\n", footer));
+    }
+
+    fn finish(self) -> String {
+        return self.text;
+    }
+}
+
+/// Adds labeled list of properties, multi-line values go to indented blocks.
+///
+/// Arguments:
+/// * `text` - report to append the list to.
+/// * `label` - line shown above the list.
+/// * `properties` - properties to list.
+/// * `opts` - report options.
+fn add_properties_list(text: &mut String, label: &str, properties: &[&TestProperty], opts: &ReportOptions) {
+    text.push_str(&format!("{}\n", label));
+    for prop in properties {
+        let value = prop.value.as_deref().unwrap_or_default();
+        if value.contains('\n') {
+            // multi-line values don't fit into a list item
+            add_indented_block(text, &prop.name, value, opts);
+            continue;
+        }
+
+        text.push_str(&format!("    {}: {}\n", prop.name, value));
+    }
+    text.push('\n');
+}
+
+/// Creates table with columns aligned by spaces and a dashed line under the header.
+/// The first row is treated as a header, the first column is aligned to the left, others are centered.
+///
+/// Arguments:
+/// * `text` - report to append the table to.
+/// * `rows` - table rows, top-to-down.
+fn create_text_table(text: &mut String, rows: Vec<Vec<String>>) {
+    if rows.len() < 2 {
+        // we need at least one header row and one value row
+        return;
+    }
+
+    let column_count = rows[0].len();
+    let mut column_widths = vec![0; column_count];
+    for row in &rows {
+        for (index, width) in column_widths.iter_mut().enumerate() {
+            if let Some(cell) = row.get(index) {
+                *width = cmp::max(*width, cell.chars().count());
+            }
+        }
+    }
+
+    for (row_index, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = (0..column_count)
+            .map(|index| {
+                let cell = row.get(index).map(String::as_str).unwrap_or_default();
+                pad_cell_text(cell, column_widths[index], index != 0)
+            })
+            .collect();
+        text.push_str(cells.join("  ").trim_end());
+        text.push('\n');
+
+        if row_index == 0 {
+            let dividers: Vec<String> = column_widths.iter().map(|width| "-".repeat(*width)).collect();
+            text.push_str(&dividers.join("  "));
+            text.push('\n');
+        }
+    }
+    text.push('\n');
+}
+
+/// Adds details of a failed test, numbered to match `[N]` reference in the breakdown table.
+///
+/// Arguments:
+/// * `text` - report to append details to.
+/// * `failure` - failed test to report.
+/// * `opts` - report options.
+fn add_failure_detail(text: &mut String, failure: &FailureDetail, opts: &ReportOptions) {
+    let FailureDetail { index, suite_name, test, result, .. } = *failure;
+    text.push_str(&format!("[{}] {}\n", index, test.name));
+
+    if let Some(suite_name) = suite_name {
+        text.push_str(&format!("    Suite: {}\n", suite_name));
+    }
+
+    if let Some(classname) = &test.classname {
+        text.push_str(&format!("    Classname: {}\n", strip_namespace(classname, &opts.lang)));
+    }
+
+    match failure.reason() {
+        (label, Some(message)) => text.push_str(&format!("    {}: {}\n", label, message)),
+        (label, None) => text.push_str(&format!("    {}\n", label)),
+    }
+
+    if let Some(body) = &result.body {
+        add_indented_block(text, "Details", body, opts);
+    }
+
+    if opts.verbose {
+        if let Some(out) = &test.system_out {
+            add_indented_block(text, "Test stdout", out, opts);
+        }
+
+        if let Some(err) = &test.system_err {
+            add_indented_block(text, "Test stderr", err, opts);
+        }
+    }
+    text.push('\n');
+}
+
+/// Adds labeled block of long text, such as failure body, indented under the failure.
+///
+/// Arguments:
+/// * `text` - report to append the block to.
+/// * `label` - label shown above the block.
+/// * `content` - the text to add.
+/// * `opts` - report options.
+fn add_indented_block(text: &mut String, label: &str, content: &str, opts: &ReportOptions) {
+    let content = prepare_code(content, opts);
+    text.push_str(&format!("    {}:\n", label));
+    for line in content.trim_end().lines() {
+        text.push_str(&format!("        {}\n", line).replace("        \n", "\n"));
+    }
+}
+
+/// Adds note explaining the inconsistent totals marker.
+fn add_inconsistent_totals_note(text: &mut String, suites: &[&TestSuite], opts: &ReportOptions) {
+    text.push_str(&format!("⚠ {}\n\n", inconsistent_totals_note(suites, opts, &TEXT_TOTALS_CELLS)));
+}
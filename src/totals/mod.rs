@@ -0,0 +1,164 @@
+use junit2md::model::*;
+
+/// Totals of a test suite or the whole aggregated report
+pub struct ReportTotals {
+    pub tests: u64,
+    pub success: u64,
+    pub skipped: u64,
+    pub disabled: u64,
+    /// Failed tests, including ones with errors
+    pub failed: u64,
+    /// Tests with errors, also counted in `failed`
+    pub errors: u64,
+    /// Time taken by the whole report, if declared
+    pub time: Option<String>,
+}
+
+impl ReportTotals {
+    /// Returns number of tests pass rate is computed against: all tests,
+    /// or only the ones that actually ran if skipped and disabled tests are excluded.
+    ///
+    /// Arguments:
+    /// * `exclude_skipped` - don't count skipped and disabled tests.
+    pub fn pass_rate_base(&self, exclude_skipped: bool) -> u64 {
+        if exclude_skipped {
+            return self.tests.saturating_sub(self.skipped + self.disabled);
+        }
+        return self.tests;
+    }
+
+    /// Returns the same totals with skipped and disabled tests counted as failed, see `--skips-as-failures`.
+    pub fn with_skips_as_failures(&self) -> ReportTotals {
+        return ReportTotals {
+            tests: self.tests,
+            success: self.success,
+            skipped: 0,
+            disabled: 0,
+            failed: self.failed + self.skipped + self.disabled,
+            errors: self.errors,
+            time: self.time.to_owned(),
+        };
+    }
+}
+
+/// Computes totals of the aggregated report.
+/// Counts declared on the root `<testsuites>` element take precedence,
+/// otherwise counts of all suites are summed.
+///
+/// Arguments:
+/// * `report` - aggregated report to compute totals for.
+pub fn compute_totals(report: &JunitReport) -> ReportTotals {
+    let suite_totals: Vec<ReportTotals> = report.testsuites.iter().map(compute_suite_totals).collect();
+    let summed_tests: u64 = suite_totals.iter().map(|totals| totals.tests).sum();
+    let summed_skipped: u64 = suite_totals.iter().map(|totals| totals.skipped).sum();
+    let summed_disabled: u64 = suite_totals.iter().map(|totals| totals.disabled).sum();
+    let summed_failed: u64 = suite_totals.iter().map(|totals| totals.failed).sum();
+    let summed_errors: u64 = suite_totals.iter().map(|totals| totals.errors).sum();
+    let summed_success: u64 = suite_totals.iter().map(|totals| totals.success).sum();
+
+    let has_declared_counts = report.tests.is_some() || report.failures.is_some() || report.errors.is_some()
+                              || report.skipped.is_some() || report.disabled.is_some();
+    if !has_declared_counts {
+        return ReportTotals {
+            tests: summed_tests,
+            success: summed_success,
+            skipped: summed_skipped,
+            disabled: summed_disabled,
+            failed: summed_failed,
+            errors: summed_errors,
+            time: report.time.to_owned(),
+        };
+    }
+
+    let tests = report.tests.unwrap_or(summed_tests);
+    let skipped = report.skipped.unwrap_or(summed_skipped);
+    let disabled = report.disabled.unwrap_or(summed_disabled);
+    let (failed, errors) = match (report.failures, report.errors) {
+        (None, None) => (summed_failed, summed_errors),
+        (failures, errors) => (failures.unwrap_or(0) + errors.unwrap_or(0), errors.unwrap_or(0)),
+    };
+
+    return ReportTotals {
+        tests,
+        success: tests.saturating_sub(skipped + disabled + failed),
+        skipped,
+        disabled,
+        failed,
+        errors,
+        time: report.time.to_owned(),
+    };
+}
+
+/// Computes totals of a single test suite from the counts declared in its attributes.
+/// When they disagree with the test cases, the suite is marked as inconsistent, see `count_success`.
+/// Use `--trust-testcases` to recount them from the test cases instead.
+/// A test with both failure and error is counted in both declared counts by some emitters,
+/// so success count is clamped to zero.
+///
+/// Arguments:
+/// * `suite` - test suite to compute totals for.
+pub fn compute_suite_totals(suite: &TestSuite) -> ReportTotals {
+    let skipped = suite.skipped.unwrap_or(0);
+    let disabled = suite.disabled_count();
+    let errors = suite.errors.unwrap_or(0);
+    let failed = suite.failures.unwrap_or(0) + errors;
+    return ReportTotals {
+        tests: suite.tests,
+        success: suite.tests.saturating_sub(skipped + disabled + failed),
+        skipped,
+        disabled,
+        failed,
+        errors,
+        time: suite.time.to_owned(),
+    };
+}
+
+/// Counts successful tests in a suite, see `compute_suite_totals`.
+/// Malformed reports can declare more failed/skipped/disabled tests than there are tests in total,
+/// or counts that don't match outcomes of test cases. Success count is never negative then,
+/// but the suite is marked as inconsistent.
+///
+/// Returns number of successful tests and whether declared totals of the suite add up.
+///
+/// Arguments:
+/// * `suite` - test suite to count tests in.
+pub fn count_success(suite: &TestSuite) -> (u64, bool) {
+    let declared = compute_suite_totals(suite);
+    let negative_tests = declared.skipped + declared.disabled + declared.failed;
+    let consistent = match count_testcases(suite) {
+        Some(counted) => same_counts(&counted, &declared),
+        None => negative_tests <= suite.tests,
+    };
+
+    return (declared.success, consistent);
+}
+
+/// Counts tests of the suite that passed only when rerun, see `TestCase::is_flaky`.
+/// They are counted as successful in totals, this tells how many of them there are.
+pub fn count_flaky(suite: &TestSuite) -> u64 {
+    return suite.testcases.iter().filter(|test| test.is_flaky()).count() as u64;
+}
+
+/// Computes totals of a suite from outcomes of its test cases.
+/// Returns `None` if the suite doesn't list all of its tests, then counts can't be trusted.
+fn count_testcases(suite: &TestSuite) -> Option<ReportTotals> {
+    if suite.testcases.is_empty() || suite.testcases.len() as u64 != suite.tests {
+        return None;
+    }
+
+    let count = |outcomes: &[TestOutcome]| suite.testcases.iter().filter(|test| outcomes.contains(&test.outcome())).count() as u64;
+    return Some(ReportTotals {
+        tests: suite.tests,
+        success: count(&[TestOutcome::Success]),
+        skipped: count(&[TestOutcome::Skipped]),
+        disabled: count(&[TestOutcome::Disabled]),
+        failed: count(&[TestOutcome::Failure, TestOutcome::Error]),
+        errors: count(&[TestOutcome::Error]),
+        time: suite.time.to_owned(),
+    });
+}
+
+/// Checks whether two totals have the same counts of each result.
+fn same_counts(a: &ReportTotals, b: &ReportTotals) -> bool {
+    return a.success == b.success && a.skipped == b.skipped && a.disabled == b.disabled && a.failed == b.failed;
+}
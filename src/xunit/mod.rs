@@ -0,0 +1,131 @@
+use serde_derive::Deserialize;
+
+use crate::model::*;
+
+/// Root of xUnit.net v2 XML report
+#[derive(Debug, Deserialize)]
+pub struct XunitAssemblies {
+    #[serde(rename = "assembly", default)]
+    pub assemblies: Vec<XunitAssembly>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct XunitAssembly {
+    pub name: String,
+    pub total: u64,
+    pub passed: Option<u64>,
+    pub failed: Option<u64>,
+    pub skipped: Option<u64>,
+    pub time: Option<String>,
+
+    #[serde(rename = "run-date")]
+    pub run_date: Option<String>,
+    #[serde(rename = "run-time")]
+    pub run_time: Option<String>,
+
+    /// Test collections, by default xUnit.net creates one per test class
+    #[serde(rename = "collection", default)]
+    pub collections: Vec<XunitCollection>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct XunitCollection {
+    pub name: String,
+    pub total: u64,
+    pub passed: Option<u64>,
+    pub failed: Option<u64>,
+    pub skipped: Option<u64>,
+    pub time: Option<String>,
+
+    #[serde(rename = "test", default)]
+    pub tests: Vec<XunitTest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct XunitTest {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub test_type: Option<String>,
+    pub method: Option<String>,
+    pub time: Option<String>,
+
+    /// One of `Pass`, `Fail`, `Skip` or `NotRun`
+    pub result: String,
+
+    /// Reason of the skip, only present for skipped tests
+    pub reason: Option<String>,
+    pub output: Option<String>,
+    pub failure: Option<XunitFailure>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct XunitFailure {
+    #[serde(rename = "exception-type")]
+    pub exception_type: Option<String>,
+    pub message: Option<String>,
+
+    #[serde(rename = "stack-trace")]
+    pub stack_trace: Option<String>,
+}
+
+impl XunitAssemblies {
+    /// Converts xUnit.net report into JUnit test suites, consuming it.
+    /// Each collection of each assembly becomes a separate test suite.
+    pub fn into_testsuites(self) -> Vec<TestSuite> {
+        let mut suites = vec![];
+        for assembly in self.assemblies {
+            let timestamp = match (&assembly.run_date, &assembly.run_time) {
+                (Some(date), Some(time)) => Some(format!("{}T{}", date, time)),
+                _ => None,
+            };
+
+            for collection in assembly.collections {
+                // default collections are named after test class, leave only the class name
+                let name = collection.name.trim_start_matches("Test collection for ").to_owned();
+                suites.push(TestSuite {
+                    name: Some(name),
+                    tests: collection.total,
+                    package: Some(assembly.name.clone()),
+                    failures: collection.failed,
+                    skipped: collection.skipped,
+                    time: collection.time,
+                    timestamp: timestamp.clone(),
+                    testcases: collection.tests.into_iter().map(XunitTest::into_testcase).collect(),
+                    ..Default::default()
+                });
+            }
+        }
+        return suites;
+    }
+}
+
+impl XunitTest {
+    /// Converts xUnit.net test into JUnit test case, consuming it.
+    pub fn into_testcase(self) -> TestCase {
+        let mut testcase = TestCase {
+            name: self.name,
+            time: self.time,
+            classname: self.test_type,
+            status: Some(self.result.clone()),
+            system_out: self.output,
+            ..Default::default()
+        };
+
+        match self.result.as_str() {
+            "Fail" => {
+                let failure = self.failure.unwrap_or_default();
+                testcase.failures.push(TestNegativeResult {
+                    error_type: failure.exception_type,
+                    message: failure.message,
+                    body: failure.stack_trace,
+                });
+            }
+            "Skip" | "NotRun" => {
+                testcase.skipped = Some(TestNegativeResult { error_type: None, message: self.reason, body: None });
+            }
+            _ => {}
+        }
+
+        return testcase;
+    }
+}
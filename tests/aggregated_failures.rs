@@ -0,0 +1,38 @@
+mod common;
+
+use common::*;
+
+/// Fixture with two suites of the same short name, each with a failing test of the same name.
+const FIXTURE: &str = "junit-report-two-failing-suites.xml";
+
+/// Returns failure details of the Markdown report, each starting at its anchor.
+fn failure_details(report: &str) -> Vec<&str> {
+    let failures = &report[report.find("\nFailures\n").unwrap_or_else(|| panic!("no failures in:\n{}", report))..];
+    return failures.split("<a id=\"c-").skip(1).collect();
+}
+
+#[test]
+fn failures_of_both_suites_are_listed() {
+    let report = generate_report(&[FIXTURE], &[]);
+    let details = failure_details(&report);
+    assert_eq!(details.len(), 2, "in:\n{}", report);
+    assert!(details[0].starts_with("0\"/>"), "in:\n{}", report);
+    assert!(details[1].starts_with("1\"/>"), "in:\n{}", report);
+}
+
+#[test]
+fn each_failure_names_its_suite() {
+    let report = generate_report(&[FIXTURE], &[]);
+    let details = failure_details(&report);
+    assert!(details[0].contains("* Suite: com.example.client.ConnectionTest\n"), "in:\n{}", report);
+    assert!(details[0].contains("expected reconnect within 100ms"), "in:\n{}", report);
+    assert!(details[1].contains("* Suite: com.example.server.ConnectionTest\n"), "in:\n{}", report);
+    assert!(details[1].contains("socket closed"), "in:\n{}", report);
+}
+
+#[test]
+fn each_failure_names_its_suite_in_text_format() {
+    let report = generate_report(&[FIXTURE], &["--format", "text"]);
+    assert!(report.contains("[0] reconnectsAfterTimeout\n    Suite: com.example.client.ConnectionTest\n"), "in:\n{}", report);
+    assert!(report.contains("[1] reconnectsAfterTimeout\n    Suite: com.example.server.ConnectionTest\n"), "in:\n{}", report);
+}
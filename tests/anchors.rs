@@ -0,0 +1,56 @@
+mod common;
+
+use std::collections::BTreeSet;
+use std::fs;
+
+use common::*;
+
+/// Returns anchor ids that occur in the report more than once.
+fn duplicate_ids(report: &str) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    let mut duplicates = vec![];
+    for chunk in report.split("id=\"").skip(1) {
+        let id = chunk.split('"').next().unwrap().to_owned();
+        if !seen.insert(id.clone()) {
+            duplicates.push(id);
+        }
+    }
+    return duplicates;
+}
+
+/// Returns names of all XML fixtures from test-reports directory.
+fn fixtures() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/test-reports"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".xml"))
+        .collect();
+    names.sort();
+    return names;
+}
+
+#[test]
+fn anchors_are_unique_in_single_reports() {
+    for fixture in fixtures() {
+        let format = if fixture.contains("xunit") {
+            "xunit"
+        } else if fixture.contains("testng") {
+            "testng"
+        } else {
+            "junit"
+        };
+
+        let report = generate_report(&[&fixture], &["--row-anchors", "--format", format]);
+        assert_eq!(duplicate_ids(&report), Vec::<String>::new(), "duplicate anchors in report for {}", fixture);
+    }
+}
+
+#[test]
+fn anchors_are_unique_in_aggregated_report() {
+    let fixtures: Vec<String> = fixtures().into_iter().filter(|name| name.contains("junit")).collect();
+    let fixtures: Vec<&str> = fixtures.iter().map(String::as_str).collect();
+
+    let report = generate_report(&fixtures, &["--row-anchors"]);
+    assert!(report.contains("id=\""), "aggregated report has no anchors to check");
+    assert_eq!(duplicate_ids(&report), Vec::<String>::new());
+}
@@ -0,0 +1,9 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn block_titles_are_escaped() {
+    let report = generate_report(&["junit-report-two-failing-suites.xml"], &["--format", "asciidoc", "--label-details", "*Trace* [1]"]);
+    assert!(report.contains("\n.+*Trace* [1]+\n[%collapsible]\n"), "unescaped title in:\n{}", report);
+}
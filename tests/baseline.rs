@@ -0,0 +1,47 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::*;
+
+/// Runs junit2md on the fixture with two failing suites and returns its stdout.
+fn two_failing_suites_report(extra_args: &[&str]) -> String {
+    let mut args = vec!["--no-details", "--bar-width", "0"];
+    args.extend_from_slice(extra_args);
+    return generate_report(&["junit-report-two-failing-suites.xml"], &args);
+}
+
+/// Writes JSON summary of the fixture, with replacements applied, to be used as a baseline.
+fn write_baseline(name: &str, replacements: &[(&str, &str)]) -> PathBuf {
+    let mut summary = two_failing_suites_report(&["--format", "json"]);
+    for (from, to) in replacements {
+        summary = summary.replace(from, to);
+    }
+
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, summary).unwrap();
+    return path;
+}
+
+#[test]
+fn unchanged_baseline_adds_nothing() {
+    let baseline = write_baseline("baseline-unchanged.json", &[]);
+    assert_eq!(two_failing_suites_report(&["--baseline", baseline.to_str().unwrap()]), two_failing_suites_report(&[]));
+}
+
+#[test]
+fn changes_since_baseline_are_annotated() {
+    let baseline = write_baseline("baseline-changed.json", &[
+        ("\"tests\": 4", "\"tests\": 3"),
+        ("\"success\": 2,\n  \"skipped\"", "\"success\": 1,\n  \"skipped\""),
+        ("\"failed\": 2,", "\"failed\": 4,"),
+        ("com.example.client.ConnectionTest", "com.example.client.LegacyTest"),
+    ]);
+    let report = two_failing_suites_report(&["--baseline", baseline.to_str().unwrap()]);
+
+    let total = table_row(&report, "**Total**");
+    assert_eq!(total[2..7], ["2 (+1)", "0", "0", "2 (-2)", "4 (+1)"], "in:\n{}", report);
+    assert!(report.contains("New suites since baseline: com.example.client.ConnectionTest"), "in:\n{}", report);
+    assert!(report.contains("Removed suites since baseline: com.example.client.LegacyTest"), "in:\n{}", report);
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use common::*;
+
+/// Returns rows of the table by classes, without header.
+fn class_rows(report: &str) -> Vec<Vec<String>> {
+    return report.lines()
+        .skip_while(|line| *line != "Breakdown by classes:")
+        .skip(4)
+        .take_while(|line| !line.is_empty())
+        .map(|line| line.split_whitespace().map(str::to_owned).collect())
+        .collect();
+}
+
+#[test]
+fn classes_with_most_tests_go_first() {
+    let report = generate_report(&["junit-report-suite-with-classes.xml"], &["--format", "text", "--by-class"]);
+    assert_eq!(class_rows(&report), vec![
+        vec!["LandscapeTest", "9", "7", "0", "2"],
+        vec!["BitmapIntegrationTest", "2", "1", "0", "1"],
+    ], "in:\n{}", report);
+}
+
+#[test]
+fn tests_without_class_are_counted_together() {
+    let report = generate_report(&["junit-report-20090516.xml"], &["--format", "text", "--by-class"]);
+    assert_eq!(class_rows(&report), vec![vec!["(none)", "8", "5", "3", "0"]], "in:\n{}", report);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::*;
+
+/// Single suite fixtures aggregated in the report, one with an error and one with skips.
+const FIXTURES: [&str; 2] = ["junit-report-error-and-failure.xml", "junit-report-status-skips.xml"];
+
+/// Returns cells of the totals table, split by whitespace, header first.
+fn totals_table(report: &str) -> Vec<Vec<String>> {
+    return report.lines()
+        .skip_while(|line| !line.contains("Suite name"))
+        .filter(|line| !line.starts_with("---"))
+        .take_while(|line| !line.is_empty())
+        .map(|line| line.split("  ").map(str::trim).filter(|cell| !cell.is_empty()).map(str::to_owned).collect())
+        .collect();
+}
+
+#[test]
+fn columns_are_selected_and_ordered() {
+    let report = generate_report(&FIXTURES, &["--format", "text", "--no-details", "--columns", "total,name,failures"]);
+    assert_eq!(totals_table(&report), vec![
+        vec!["Total", "Suite name", "Failures"],
+        vec!["3", "ErrorAndFailureTest", "2"],
+        vec!["4", "IndexTest", "0"],
+        vec!["7", "Total", "2"],
+    ], "in:\n{}", report);
+}
+
+#[test]
+fn errors_are_split_out_of_failures() {
+    let report = generate_report(&FIXTURES, &["--format", "text", "--no-details", "--columns", "name,failures,errors,skipped"]);
+    assert_eq!(totals_table(&report), vec![
+        vec!["Suite name", "Failures", "Errors", "Skipped"],
+        vec!["ErrorAndFailureTest", "1", "1", "0"],
+        vec!["IndexTest", "0", "0", "2"],
+        vec!["Total", "1", "1", "2"],
+    ], "in:\n{}", report);
+}
+
+#[test]
+fn unknown_column_is_an_error() {
+    let output = run(&FIXTURES, &["--format", "text", "--columns", "name,flakiness"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown column 'flakiness'"));
+}
@@ -0,0 +1,59 @@
+//! Helpers shared by integration tests, each test file uses only some of them.
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Options of the plain text report with just the counts, see `text_totals_rows`.
+pub const TEXT_COUNTS: [&str; 6] = ["--format", "text", "--no-details", "--no-percent", "--bar-width", "0"];
+
+/// Returns path of the fixture in test-reports directory. Absolute paths, e.g. of generated reports, are kept as is.
+pub fn fixture_path(name: &str) -> String {
+    return Path::new(env!("CARGO_MANIFEST_DIR")).join("test-reports").join(name).to_string_lossy().into_owned();
+}
+
+/// Creates junit2md command that ignores config files, so only the given arguments affect the report.
+pub fn junit2md() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_junit2md"));
+    command.arg("--no-config");
+    return command;
+}
+
+/// Runs junit2md quietly on the fixtures with the given arguments.
+///
+/// Arguments:
+/// * `fixtures` - input files, see `fixture_path`.
+/// * `args` - options passed before the input files.
+pub fn run(fixtures: &[&str], args: &[&str]) -> Output {
+    return junit2md()
+        .arg("--quiet")
+        .args(args)
+        .args(fixtures.iter().map(|name| fixture_path(name)))
+        .output()
+        .expect("failed to run junit2md");
+}
+
+/// Runs junit2md like `run` and returns the report it printed, failing the test if junit2md failed.
+pub fn generate_report(fixtures: &[&str], args: &[&str]) -> String {
+    let output = run(fixtures, args);
+    assert!(output.status.success(), "junit2md failed with args {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+    return String::from_utf8(output.stdout).unwrap();
+}
+
+/// Returns trimmed cells of the first Markdown table row starting with the given cell.
+pub fn table_row(report: &str, first_cell: &str) -> Vec<String> {
+    return report.lines()
+        .map(|line| line.trim_matches('|').split('|').map(|cell| cell.trim().to_owned()).collect::<Vec<String>>())
+        .find(|cells| cells[0] == first_cell)
+        .unwrap_or_else(|| panic!("no {} row in:\n{}", first_cell, report));
+}
+
+/// Returns rows of the totals table of plain text report without header, cells split by whitespace.
+pub fn text_totals_rows(report: &str) -> Vec<Vec<String>> {
+    return report.lines()
+        .skip_while(|line| !line.starts_with("Suite name"))
+        .skip(2)
+        .take_while(|line| !line.is_empty())
+        .map(|line| line.split_whitespace().map(str::to_owned).collect())
+        .collect();
+}
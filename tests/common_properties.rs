@@ -0,0 +1,32 @@
+mod common;
+
+use common::*;
+
+/// Fixture with two suites sharing some properties.
+const FIXTURE: &str = "junit-report-common-properties.xml";
+
+#[test]
+fn shared_properties_are_listed_once_in_verbose_mode() {
+    let report = generate_report(&[FIXTURE], &["-v"]);
+    assert!(report.contains("Common properties:\n* git.sha: 3f9c2d1\n* git.branch: main\n"), "in:\n{}", report);
+    assert!(!report.contains("worker"), "properties with different values should not be listed in:\n{}", report);
+}
+
+#[test]
+fn root_properties_are_listed_before_shared_ones() {
+    let report = generate_report(&[FIXTURE], &["-v"]);
+    assert!(report.contains("Properties:\n* ci.build: 1842\n\nCommon properties:\n"), "in:\n{}", report);
+}
+
+#[test]
+fn root_properties_are_masked() {
+    let report = generate_report(&[FIXTURE], &["-v", "--mask-property", "[0-9]{4}"]);
+    assert!(report.contains(r"* ci.build: \*\*\*"), "in:\n{}", report);
+}
+
+#[test]
+fn properties_are_not_listed_in_brief_mode() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert!(!report.contains("Common properties"), "in:\n{}", report);
+    assert!(!report.contains("ci.build"), "in:\n{}", report);
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use std::fs;
+use std::io::Write;
+use std::process::Stdio;
+
+use common::*;
+
+#[test]
+fn concatenated_suites_are_aggregated() {
+    let report = generate_report(&["junit-report-concatenated.xml"], &["--format", "text", "--bar-width", "0"]);
+    assert!(report.starts_with("Aggregated test report (3 tests, 1 failed)"), "unexpected report:\n{}", report);
+
+    let suite_rows: Vec<&str> = report.lines().filter(|line| line.starts_with("test_")).collect();
+    assert_eq!(suite_rows.len(), 2, "unexpected suite rows:\n{}", report);
+    assert!(suite_rows[0].starts_with("test_parser "));
+    assert!(suite_rows[1].starts_with("test_writer "));
+}
+
+#[test]
+fn concatenated_suites_are_split_when_piped() {
+    let mut child = junit2md()
+        .args(["--quiet", "--format", "text", "--bar-width", "0", "/dev/stdin"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run junit2md");
+    let xml = fs::read(fixture_path("junit-report-concatenated.xml")).unwrap();
+    child.stdin.take().unwrap().write_all(&xml).unwrap();
+
+    let output = child.wait_with_output().expect("failed to run junit2md");
+    assert!(output.status.success());
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(report.starts_with("Aggregated test report (3 tests, 1 failed)"), "unexpected report:\n{}", report);
+}
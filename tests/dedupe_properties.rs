@@ -0,0 +1,49 @@
+mod common;
+
+use std::process::Output;
+
+use common::*;
+
+/// Runs junit2md verbosely on the fixture with repeated properties, with the given extra arguments.
+/// Warnings are not suppressed, so they can be checked.
+fn run_verbose(args: &[&str]) -> Output {
+    return junit2md()
+        .arg("-v")
+        .args(args)
+        .arg(fixture_path("junit-report-duplicate-properties.xml"))
+        .output()
+        .expect("failed to run junit2md");
+}
+
+/// Extracts property list items from the report.
+fn property_lines(output: &Output) -> Vec<String> {
+    assert!(output.status.success(), "junit2md failed: {}", String::from_utf8_lossy(&output.stderr));
+    let report = String::from_utf8_lossy(&output.stdout);
+    return report.lines()
+        .skip_while(|line| *line != "Properties:")
+        .skip(1)
+        .take_while(|line| line.starts_with("* "))
+        .map(str::to_owned)
+        .collect();
+}
+
+#[test]
+fn repeated_properties_are_listed_by_default() {
+    let output = run_verbose(&[]);
+    assert_eq!(property_lines(&output), ["* tag: smoke", "* git.sha: 3f9c2d1", "* tag: api", "* tag: nightly"]);
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("property tag"));
+}
+
+#[test]
+fn last_value_is_kept_with_warning() {
+    let output = run_verbose(&["--dedupe-properties", "last"]);
+    assert_eq!(property_lines(&output), ["* tag: nightly", "* git.sha: 3f9c2d1"]);
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Suite com.example.api.UsersTest: property tag is declared 3 times, keeping the last value"));
+}
+
+#[test]
+fn values_are_grouped_into_single_entry() {
+    let output = run_verbose(&["--dedupe-properties", "group"]);
+    assert_eq!(property_lines(&output), ["* tag: smoke, api, nightly", "* git.sha: 3f9c2d1"]);
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::*;
+
+/// Fixture with tests disabled by `status="disabled"` and by `<disabled>` element, without suite-level `disabled` count.
+const FIXTURE: &str = "junit-report-disabled-tests.xml";
+
+#[test]
+fn disabled_tests_have_their_own_status() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert_eq!(table_row(&report, "migratesLegacyFlags")[1], "⊘", "in:\n{}", report);
+    assert_eq!(table_row(&report, "syncsRemoteFlags")[1], "⊘", "in:\n{}", report);
+    assert_eq!(table_row(&report, "readsFlag")[1], "✓", "in:\n{}", report);
+
+    let report = generate_report(&[FIXTURE], &["--format", "text"]);
+    assert!(report.contains("\nsyncsRemoteFlags       ⊘ "), "in:\n{}", report);
+}
+
+#[test]
+fn disabled_tests_are_counted_in_totals() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert_eq!(table_row(&report, "Disabled")[1..], ["2", "50.0%"], "in:\n{}", report);
+    assert_eq!(table_row(&report, "**Success**")[1..], ["2", "50.0%"], "in:\n{}", report);
+    assert_eq!(table_row(&report, "Skipped")[1], "0", "in:\n{}", report);
+    assert!(!report.contains("[^totals]"), "in:\n{}", report);
+}
+
+#[test]
+fn disabled_tests_are_labeled_in_suite_status() {
+    let report = generate_report(&[FIXTURE], &["--suite-status"]);
+    assert!(report.contains("\n✓ 2 passed · ⊘ 2 disabled\n"), "in:\n{}", report);
+}
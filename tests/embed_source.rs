@@ -0,0 +1,43 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::*;
+
+#[test]
+fn source_is_appended_under_its_path() {
+    let fixture = fixture_path("junit-report-status-skips.xml");
+    let report = generate_report(&[&fixture], &["--embed-source"]);
+
+    let source = fs::read_to_string(&fixture).unwrap();
+    let expected = format!("<summary>{}</summary>\n\n```xml\n{}```\n", fixture.replace('_', r"\_"), source);
+    assert!(report.contains(&expected), "in:\n{}", report);
+}
+
+#[test]
+fn fence_is_longer_than_backticks_in_source() {
+    let report_file = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("junit-report-backticks.xml");
+    fs::write(&report_file, concat!(
+        "<testsuite name=\"FenceTest\" tests=\"1\" failures=\"1\">\n",
+        "  <testcase name=\"fence\"><failure message=\"````\">````</failure></testcase>\n",
+        "</testsuite>\n",
+    )).unwrap();
+
+    let report = generate_report(&[report_file.to_str().unwrap()], &["--embed-source"]);
+    assert!(report.contains("\n`````xml\n<testsuite"), "in:\n{}", report);
+    assert!(report.contains("</testsuite>\n`````\n"), "in:\n{}", report);
+}
+
+#[test]
+fn source_is_shortened_with_tail_output() {
+    let report = generate_report(&["junit-report-status-skips.xml"], &["--embed-source", "--tail-output", "1"]);
+    assert!(report.contains("```xml\n... (6 earlier lines omitted)\n</testsuite>\n```\n"), "in:\n{}", report);
+}
+
+#[test]
+fn source_is_indented_under_its_path_in_text() {
+    let fixture = fixture_path("junit-report-status-skips.xml");
+    let report = generate_report(&[&fixture], &["--format", "text", "--embed-source", "--tail-output", "1"]);
+    assert!(report.contains(&format!("Sources:\n\n{}\n    ... (6 earlier lines omitted)\n    </testsuite>\n", fixture)), "in:\n{}", report);
+}
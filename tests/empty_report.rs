@@ -0,0 +1,28 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::*;
+
+#[test]
+fn empty_aggregated_report_is_not_an_error() {
+    let report = generate_report(&["junit-report-empty-testsuites.xml"], &[]);
+    let mut lines = report.lines().skip_while(|line| line.is_empty());
+    assert_eq!(lines.next(), Some("Aggregated test report (0 tests, 0 failed)"));
+    assert!(lines.next().unwrap().starts_with("==="));
+    assert_eq!(lines.next(), Some(""));
+    assert_eq!(lines.next(), Some("No test suites found"));
+}
+
+#[test]
+fn empty_aggregated_report_with_attributes_is_not_a_suite() {
+    let report_file = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("junit-report-empty-testsuites-attrs.xml");
+    fs::write(&report_file, r#"<testsuites tests="0"></testsuites>"#).expect("failed to write report");
+
+    let report = generate_report(&[report_file.to_str().unwrap()], &[]);
+    assert!(!report.contains("Unnamed suite"), "empty aggregated report is reported as a suite:\n{}", report);
+    let mut lines = report.lines().skip_while(|line| line.is_empty());
+    assert_eq!(lines.next(), Some("Aggregated test report (0 tests, 0 failed)"));
+    assert!(report.contains("No test suites found"), "no empty report note in:\n{}", report);
+}
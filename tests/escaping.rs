@@ -0,0 +1,52 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::*;
+
+/// Fixture with test names full of Markdown syntax.
+const FIXTURE: &str = "junit-report-markdown-names.xml";
+
+#[test]
+fn underscores_in_test_names_are_escaped() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert!(report.contains(r"|test\_\_\_init\_\_ "), "unescaped name in:\n{}", report);
+    assert!(!report.contains("test___init__"), "unescaped name in:\n{}", report);
+}
+
+#[test]
+fn asterisks_and_brackets_in_test_names_are_escaped() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert!(report.contains(r"|test\_glob\[\*.xml\]"), "unescaped name in:\n{}", report);
+    assert!(report.contains(r"### test\_\*\*kwargs ###"), "unescaped failure header in:\n{}", report);
+}
+
+#[test]
+fn class_names_are_escaped_like_test_names() {
+    let report_file = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("junit-report-markdown-classnames.xml");
+    fs::write(&report_file, concat!(
+        "<testsuite name=\"tests\" tests=\"1\">\n",
+        "  <testcase classname=\"tests.test_module|Pipe*x\" name=\"test_one\"/>\n",
+        "</testsuite>\n",
+    )).unwrap();
+
+    let report = generate_report(&[report_file.to_str().unwrap()], &["--show-classname"]);
+    // escaped pipe would split the cell for table_row, look for the whole row instead
+    assert!(report.contains(r"|test\_one    |tests.test\_module\|Pipe\*x|"), "unescaped class name in:\n{}", report);
+}
+
+#[test]
+fn messages_with_backticks_and_line_breaks_stay_in_code_span() {
+    let report_file = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("junit-report-markdown-messages.xml");
+    fs::write(&report_file, concat!(
+        "<testsuite name=\"tests\" tests=\"2\" failures=\"1\" skipped=\"1\">\n",
+        "  <testcase name=\"test_fail\"><failure message=\"expected `a`&#10;&#10;# got b\"/></testcase>\n",
+        "  <testcase name=\"test_skip\"><skipped message=\"needs `--slow`\"/></testcase>\n",
+        "</testsuite>\n",
+    )).unwrap();
+
+    let report = generate_report(&[report_file.to_str().unwrap()], &[]);
+    assert!(report.contains("* Fail reason: ``expected `a`  # got b``\n"), "broken fail reason in:\n{}", report);
+    assert!(report.contains("* Skipped: `` needs `--slow` ``\n"), "broken skip reason in:\n{}", report);
+}
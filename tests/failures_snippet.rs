@@ -0,0 +1,44 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn failed_tests_of_all_inputs_are_listed() {
+    let snippet = generate_report(&["junit-report-unsorted-failures.xml", "junit-report-error-and-failure.xml"], &["--failures-snippet"]);
+    assert_eq!(snippet, "* OrderTest.rejectsEmptyCart: no exception thrown\n\
+                         * CartTest.removesItems: item is still in cart\n\
+                         * OrderTest.appliesCoupon: discount was not applied\n\
+                         * CartTest.clearsCart: cart is not empty\n\
+                         * ErrorAndFailureTest.testBoth: connection reset\n");
+}
+
+#[test]
+fn skipped_and_flaky_tests_are_not_listed() {
+    let snippet = generate_report(&["junit-report-flaky.xml", "junit-report-skip-body.xml"], &["--failures-snippet"]);
+    assert_eq!(snippet, "* CacheTest.loadsSnapshot: snapshot is corrupted\n");
+}
+
+#[test]
+fn min_severity_limits_listed_tests() {
+    let snippet = generate_report(&["junit-report-unsorted-failures.xml", "junit-report-error-and-failure.xml"], &["--failures-snippet", "--min-severity", "error"]);
+    assert_eq!(snippet, "* ErrorAndFailureTest.testBoth: connection reset\n");
+}
+
+#[test]
+fn max_failures_limits_listed_tests() {
+    let snippet = generate_report(&["junit-report-unsorted-failures.xml"], &["--failures-snippet", "--max-failures", "1"]);
+    assert_eq!(snippet, "* OrderTest.rejectsEmptyCart: no exception thrown\n* ... and 3 more failures\n");
+}
+
+#[test]
+fn passing_report_has_empty_snippet() {
+    let snippet = generate_report(&["junit-report-common-properties.xml"], &["--failures-snippet"]);
+    assert_eq!(snippet, "");
+}
+
+#[test]
+fn snippet_conflicts_with_compact() {
+    let output = run(&["junit-report-unsorted-failures.xml"], &["--failures-snippet", "--compact"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--compact"));
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::*;
+
+/// Fixture with rerun failures of flaky tests.
+const FIXTURE: &str = "junit-report-flaky.xml";
+
+/// Returns the section of the report starting at the given header, up to the next section or the end.
+fn section<'a>(report: &'a str, header: &str) -> &'a str {
+    let start = match report.find(header) {
+        Some(start) => start,
+        None => return "",
+    };
+    let rest = &report[start + header.len()..];
+    let end = ["\nFailures:", "\nFlaky tests:"].iter().filter_map(|next| rest.find(next)).min().unwrap_or(rest.len());
+    return &rest[..end];
+}
+
+#[test]
+fn flaky_tests_are_not_shown_by_default() {
+    let report = generate_report(&[FIXTURE], &["--format", "text"]);
+    assert!(!report.contains("Flaky"), "in:\n{}", report);
+}
+
+#[test]
+fn flaky_tests_are_counted_as_successful() {
+    let report = generate_report(&[FIXTURE], &["--format", "text", "--show-flaky"]);
+    assert!(report.contains("Failed           1"), "in:\n{}", report);
+    assert!(report.contains("Success          3"), "in:\n{}", report);
+    assert!(report.contains("Flaky            2"), "in:\n{}", report);
+}
+
+#[test]
+fn failed_runs_of_flaky_tests_are_listed() {
+    let report = generate_report(&[FIXTURE], &["--format", "text", "--show-flaky"]);
+    let flaky = section(&report, "Flaky tests:");
+    assert!(flaky.contains("2 tests passed only when rerun"), "in:\n{}", report);
+    assert!(flaky.contains("evictsOldEntries\n    Failed run 1: expected size 2 but was 3"), "in:\n{}", report);
+    assert!(flaky.contains("    Failed run 2: Connection reset"), "in:\n{}", report);
+    assert!(flaky.contains("CacheTest.java:31"), "in:\n{}", report);
+    assert!(flaky.contains("expiresEntries\n    Failed run 1: timed out after 1s"), "in:\n{}", report);
+    assert!(!flaky.contains("loadsSnapshot"), "in:\n{}", report);
+}
+
+#[test]
+fn flaky_tests_are_not_in_failures() {
+    let report = generate_report(&[FIXTURE], &["--format", "text", "--show-flaky"]);
+    let failures = section(&report, "Failures:");
+    assert!(failures.contains("loadsSnapshot"), "in:\n{}", report);
+    assert!(!failures.contains("evictsOldEntries"), "in:\n{}", report);
+    assert!(!failures.contains("expiresEntries"), "in:\n{}", report);
+}
+
+#[test]
+fn markdown_report_has_flaky_section() {
+    let report = generate_report(&[FIXTURE], &["--format", "markdown", "--show-flaky"]);
+    assert!(report.contains("Flaky tests\n-----------"), "in:\n{}", report);
+    assert!(report.contains("* Failed run 1: `expected size 2 but was 3`"), "in:\n{}", report);
+    assert!(report.contains("|   Flaky   |       2       |  50.0%   |"), "in:\n{}", report);
+}
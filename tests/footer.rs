@@ -0,0 +1,41 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use common::*;
+
+#[test]
+fn footer_command_leaves_out_option_values() {
+    let report = generate_report(&["junit-report-retries.xml"], &["--footer", "--footer-command", "--title", "token-123", "--label-details=secret-456"]);
+    let footer = report.trim_end().lines().last().unwrap();
+    assert_eq!(footer, format!("*Generated by junit2md {} from 1 input file with junit2md --no-config --quiet --footer --footer-command --title --label-details*",
+                               env!("CARGO_PKG_VERSION")));
+}
+
+#[test]
+fn footer_command_requires_footer() {
+    let output = run(&["junit-report-retries.xml"], &["--footer-command"]);
+    assert!(!output.status.success(), "footer command without footer was accepted");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--footer-command requires the footer"));
+}
+
+#[test]
+fn footer_from_config_file_allows_footer_command() {
+    let config_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("footer-config");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(config_dir.join(".junit2md.toml"), "footer = true\n").unwrap();
+
+    // config file is only read without --no-config, so the common helpers can't be used
+    let output = Command::new(env!("CARGO_BIN_EXE_junit2md"))
+        .current_dir(&config_dir)
+        .env("HOME", &config_dir)
+        .args(["--quiet", "--footer-command"])
+        .arg(fixture_path("junit-report-retries.xml"))
+        .output()
+        .expect("failed to run junit2md");
+    assert!(output.status.success(), "junit2md failed: {}", String::from_utf8_lossy(&output.stderr));
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(report.trim_end().ends_with("with junit2md --quiet --footer-command*"), "unexpected footer in:\n{}", report);
+}
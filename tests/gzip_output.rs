@@ -0,0 +1,45 @@
+mod common;
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+
+use common::*;
+
+/// Writes report of the sample fixture to the output path.
+fn write_report(output: &Path, extra_args: &[&str]) {
+    let mut args = vec!["--output", output.to_str().unwrap()];
+    args.extend_from_slice(extra_args);
+    generate_report(&["junit-report-sample.xml"], &args);
+}
+
+/// Reads and decompresses gzipped file.
+fn read_gzip_file(path: &Path) -> String {
+    let mut content = String::new();
+    GzDecoder::new(fs::File::open(path).unwrap()).read_to_string(&mut content).unwrap();
+    return content;
+}
+
+#[test]
+fn gz_output_is_compressed() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let plain = dir.join("gzip-output-plain.md");
+    let compressed = dir.join("gzip-output.md.gz");
+    write_report(&plain, &[]);
+    write_report(&compressed, &[]);
+
+    let plain_report = fs::read_to_string(&plain).unwrap();
+    assert!(fs::metadata(&compressed).unwrap().len() < plain_report.len() as u64);
+    assert_eq!(read_gzip_file(&compressed), plain_report);
+}
+
+#[test]
+fn gz_suffix_is_kept_for_each_format() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    write_report(&dir.join("gzip-formats.md.gz"), &["--format", "markdown", "--format", "text"]);
+
+    assert!(read_gzip_file(&dir.join("gzip-formats.md.gz")).contains("Aggregated test report"));
+    assert!(read_gzip_file(&dir.join("gzip-formats.txt.gz")).contains("Aggregated test report"));
+}
@@ -0,0 +1,21 @@
+mod common;
+
+use common::*;
+
+/// Fixture with test names full of markup characters.
+const FIXTURE: &str = "junit-report-markdown-names.xml";
+
+#[test]
+fn failure_details_are_collapsible() {
+    let report = generate_report(&[FIXTURE], &["--format", "html"]);
+    assert!(report.contains("<h3 id=\"c-0\">test_**kwargs</h3>"), "no failure header in:\n{}", report);
+    assert!(report.contains("<details>\n<summary>Click to show details</summary>\n<pre>"), "no collapsible details in:\n{}", report);
+    assert!(report.contains("<a href=\"#c-0\">[0]</a>"), "no link to failure in:\n{}", report);
+}
+
+#[test]
+fn user_strings_are_escaped() {
+    let report = generate_report(&[FIXTURE], &["--format", "html"]);
+    assert!(report.contains("unexpected keyword argument &#39;name&#39;"), "unescaped failure body in:\n{}", report);
+    assert!(!report.contains("'name'"), "unescaped failure body in:\n{}", report);
+}
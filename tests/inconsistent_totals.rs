@@ -0,0 +1,24 @@
+mod common;
+
+use common::*;
+
+/// Fixture declaring more failures than tests.
+const FIXTURE: &str = "junit-report-inconsistent-totals.xml";
+
+#[test]
+fn success_count_does_not_underflow() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert_eq!(table_row(&report, "Failed")[1], "4", "in:\n{}", report);
+    assert_eq!(table_row(&report, "**Success**")[1], "0 ⚠[^totals]", "in:\n{}", report);
+}
+
+#[test]
+fn inconsistent_totals_have_a_note() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert!(report.contains("\n[^totals]: Declared totals don't add up or don't match test cases in: OverReportedTest (2 tests)\n"),
+            "in:\n{}", report);
+
+    let report = generate_report(&[FIXTURE], &["--format", "text"]);
+    assert!(report.contains("\n⚠ Declared totals don't add up or don't match test cases in: OverReportedTest (2 tests)\n"),
+            "in:\n{}", report);
+}
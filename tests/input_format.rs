@@ -0,0 +1,18 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn xunit_input_is_selected_with_format() {
+    let report = generate_report(&["xunit-report-sample.xml"], &["--format", "xunit"]);
+    assert!(report.trim_start().starts_with("Aggregated test report (5 tests, 1 failed)\n==="), "unexpected report:\n{}", report);
+}
+
+#[test]
+fn input_format_can_be_combined_with_output_format() {
+    let report = generate_report(&["xunit-report-sample.xml"], &["--format", "xunit,text"]);
+    assert!(report.starts_with("Aggregated test report (5 tests, 1 failed)\n\n"), "unexpected report:\n{}", report);
+
+    let report = generate_report(&["testng-results-sample.xml"], &["--format", "testng", "--format", "text"]);
+    assert!(report.starts_with("Aggregated test report (4 tests, 1 failed)\n\n"), "unexpected report:\n{}", report);
+}
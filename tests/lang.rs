@@ -0,0 +1,56 @@
+mod common;
+
+use common::*;
+
+/// Fixture with Java, Kotlin and C# test names.
+const FIXTURE: &str = "junit-report-mixed-languages.xml";
+
+/// Returns test names and classes of the testcase breakdown, split by runs of two or more spaces.
+fn testcase_rows(report: &str) -> Vec<Vec<String>> {
+    return report.lines()
+        .skip_while(|line| *line != "Breakdown by testcases:")
+        .skip(4)
+        .take_while(|line| !line.is_empty())
+        .map(|line| line.split("  ").map(str::trim).filter(|cell| !cell.is_empty()).take(2).map(str::to_owned).collect())
+        .collect();
+}
+
+#[test]
+fn only_java_names_are_shortened_by_default() {
+    let report = generate_report(&[FIXTURE], &["--format", "text", "--show-classname"]);
+    let rows = testcase_rows(&report);
+    assert_eq!(rows[0], vec!["sumsLines[rate=1.5]", "InvoiceTest"], "in:\n{}", report);
+    assert_eq!(rows[3], vec!["Example.Billing.Tests.CalculatorTests.Adds(1.5,2)", "Example.Billing.Tests.CalculatorTests+Nested"],
+               "in:\n{}", report);
+}
+
+#[test]
+fn java_and_csharp_names_are_shortened_together() {
+    let report = generate_report(&[FIXTURE], &["--format", "text", "--show-classname", "--lang", "java", "--lang", "csharp"]);
+    assert_eq!(testcase_rows(&report), vec![
+        vec!["sumsLines[rate=1.5]", "InvoiceTest"],
+        vec!["roundsTotals", "InvoiceTest"],
+        vec!["com.example.billing.InvoiceTest.keeps totals positive()", "InvoiceTest"],
+        vec!["Adds(1.5,2)", "CalculatorTests+Nested"],
+        vec!["Divides(a: \"x.y\")", "CalculatorTests"],
+        vec!["opens page.html", "✓"],
+    ], "in:\n{}", report);
+}
+
+#[test]
+fn languages_can_be_given_as_list() {
+    let report = generate_report(&[FIXTURE], &["--format", "text", "--show-classname", "--lang", "csharp,java,kotlin"]);
+    let rows = testcase_rows(&report);
+    assert_eq!(rows[0][0], "sumsLines[rate=1.5]", "in:\n{}", report);
+    assert_eq!(rows[2][0], "keeps totals positive()", "in:\n{}", report);
+    assert_eq!(rows[3][0], "Adds(1.5,2)", "in:\n{}", report);
+}
+
+#[test]
+fn csharp_alone_leaves_java_parameters_intact() {
+    let report = generate_report(&[FIXTURE], &["--format", "text", "--show-classname", "--lang", "csharp"]);
+    let rows = testcase_rows(&report);
+    assert_eq!(rows[0][0], "com.example.billing.InvoiceTest.sumsLines[rate=1.5]", "in:\n{}", report);
+    assert_eq!(rows[0][1], "InvoiceTest", "in:\n{}", report);
+    assert_eq!(rows[4][0], "Divides(a: \"x.y\")", "in:\n{}", report);
+}
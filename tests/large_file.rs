@@ -0,0 +1,69 @@
+mod common;
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use common::*;
+
+const SUITES: usize = 20;
+const TESTS_PER_SUITE: usize = 2000;
+
+/// Writes a large aggregated report, every tenth test of each suite fails.
+/// Failure bodies contain markup characters, so splitting documents has to skip CDATA sections.
+fn generate_large_report(name: &str) -> PathBuf {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+    for suite in 0..SUITES {
+        writeln!(xml, "  <testsuite name=\"suite_{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\" time=\"1.0\">",
+            suite, TESTS_PER_SUITE, TESTS_PER_SUITE / 10).unwrap();
+        for test in 0..TESTS_PER_SUITE {
+            if test % 10 != 0 {
+                writeln!(xml, "    <testcase name=\"test_{}\" classname=\"suite_{}\" time=\"0.001\"/>", test, suite).unwrap();
+                continue;
+            }
+            writeln!(xml, "    <testcase name=\"test_{}\" classname=\"suite_{}\" time=\"0.001\">", test, suite).unwrap();
+            writeln!(xml, "      <failure message=\"expected &lt;1&gt;\"><![CDATA[</testsuite> <testsuite> -->]]></failure>").unwrap();
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, xml).expect("failed to write generated report");
+    return path;
+}
+
+#[test]
+fn large_aggregated_report_is_parsed() {
+    let report_file = generate_large_report("junit-report-large.xml");
+    let report = generate_report(&[report_file.to_str().unwrap()], &["--format", "text", "--bar-width", "0"]);
+    assert!(report.starts_with("Aggregated test report (40,000 tests, 4,000 failed)"), "unexpected report title:\n{}", report.lines().next().unwrap_or_default());
+
+    let suite_rows = report.lines().filter(|line| line.starts_with("suite_")).count();
+    assert_eq!(suite_rows, SUITES);
+}
+
+#[test]
+fn report_piped_through_stdin_is_parsed() {
+    let report_file = generate_large_report("junit-report-piped.xml");
+    let mut child = junit2md()
+        .args(["--quiet", "--format", "text", "--bar-width", "0", "/dev/stdin"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run junit2md");
+    // stdin can be read only once, so the report must not be reopened for parsing
+    let xml = fs::read(&report_file).unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = std::thread::spawn(move || stdin.write_all(&xml));
+
+    let output = child.wait_with_output().expect("failed to run junit2md");
+    writer.join().unwrap().expect("failed to pipe report");
+    assert!(output.status.success(), "junit2md failed: {}", String::from_utf8_lossy(&output.stderr));
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(report.starts_with("Aggregated test report (40,000 tests, 4,000 failed)"), "unexpected report title:\n{}", report.lines().next().unwrap_or_default());
+}
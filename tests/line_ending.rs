@@ -0,0 +1,17 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn report_has_lf_line_endings_by_default() {
+    let report = generate_report(&["junit-report-sample.xml"], &[]);
+    assert!(!report.contains('\r'));
+}
+
+#[test]
+fn crlf_line_endings_are_applied_to_the_whole_report() {
+    let lf_report = generate_report(&["junit-report-sample.xml"], &[]);
+    let crlf_report = generate_report(&["junit-report-sample.xml"], &["--line-ending", "crlf"]);
+    assert!(!crlf_report.replace("\r\n", "").contains('\n'), "bare LF left in:\n{}", crlf_report);
+    assert_eq!(crlf_report.replace("\r\n", "\n"), lf_report);
+}
@@ -0,0 +1,20 @@
+mod common;
+
+use common::*;
+
+/// Fixture with a suite without `name` attribute.
+const FIXTURE: &str = "junit-report-nameless-suite.xml";
+
+#[test]
+fn nameless_suite_is_reported_as_unnamed() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert_eq!(report.lines().find(|line| !line.is_empty()), Some("Unnamed suite (2 tests)"), "in:\n{}", report);
+    assert!(report.contains("### login page responds ###"), "in:\n{}", report);
+}
+
+#[test]
+fn nameless_suite_is_kept_in_aggregated_report() {
+    let report = generate_report(&[FIXTURE, "junit-report-retries.xml"], &["--bar-width", "0"]);
+    assert_eq!(table_row(&report, "[Unnamed suite](#suite-0)")[1..7], ["0.052", "1", "0", "0", "1", "2"], "in:\n{}", report);
+    assert!(report.contains("* Suite: Unnamed suite\n"), "in:\n{}", report);
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use std::process::Output;
+
+use common::*;
+
+/// Runs junit2md with text output on the fixture with two suites of the same short name.
+/// Warnings are not suppressed, so they can be checked.
+fn run_with_warnings(extra_args: &[&str]) -> Output {
+    let output = junit2md()
+        .args(["--format", "text", "--no-details"])
+        .args(extra_args)
+        .arg(fixture_path("junit-report-two-failing-suites.xml"))
+        .output()
+        .expect("failed to run junit2md");
+
+    assert!(output.status.success(), "junit2md failed: {}", String::from_utf8_lossy(&output.stderr));
+    return output;
+}
+
+#[test]
+fn only_selected_suite_is_reported() {
+    let report = String::from_utf8(run_with_warnings(&["--only-suite", "com.example.server.ConnectionTest"]).stdout).unwrap();
+    assert!(report.starts_with("Aggregated test report (2 tests, 1 failed)"), "in:\n{}", report);
+}
+
+#[test]
+fn only_suite_is_combined_with_suite_filter() {
+    let args = ["--only-suite", "com.example.server.ConnectionTest", "--suite-filter", "client"];
+    let report = String::from_utf8(run_with_warnings(&args).stdout).unwrap();
+    assert!(report.contains("No test suites found"), "in:\n{}", report);
+}
+
+#[test]
+fn unknown_suite_name_is_reported() {
+    let output = run_with_warnings(&["--only-suite", "com.example.server.ConnectionTest", "--only-suite", "ConnectionTest"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("No test suite named ConnectionTest to report"), "in:\n{}", stderr);
+    assert!(!stderr.contains("named com.example.server.ConnectionTest"), "in:\n{}", stderr);
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn single_suite_totals_have_only_counts() {
+    let report = generate_report(&["junit-report-retries.xml"], &["--no-percent", "--bar-width", "0"]);
+    assert!(report.contains("\n|   Type    |Number of tests|\n"), "in:\n{}", report);
+    assert!(!report.contains('%'), "in:\n{}", report);
+}
+
+#[test]
+fn aggregated_totals_have_no_percent_and_pass_rate() {
+    let report = generate_report(&["junit-report-two-failing-suites.xml"], &["--no-percent", "--bar-width", "0"]);
+    let header = report.lines().find(|line| line.contains("Suite name")).unwrap();
+    assert!(header.ends_with("|Total|"), "in:\n{}", report);
+    assert!(!report.contains("Pass rate"), "in:\n{}", report);
+}
+
+#[test]
+fn half_failing_suite_has_one_decimal_percentages() {
+    let report = generate_report(&["junit-report-nameless-suite.xml"], &[]);
+    assert_eq!(table_row(&report, "Failed")[1..], ["1", "50.0%"], "in:\n{}", report);
+    assert_eq!(table_row(&report, "**Success**")[1..], ["1", "50.0%"], "in:\n{}", report);
+}
+
+#[test]
+fn half_failing_suites_have_one_decimal_pass_rate() {
+    let report = generate_report(&["junit-report-two-failing-suites.xml"], &["--bar-width", "0"]);
+    let header = table_row(&report, "Suite name");
+    let pass_rate = header.iter().position(|cell| cell == "Pass rate").unwrap_or_else(|| panic!("no pass rate in:\n{}", report));
+    assert_eq!(table_row(&report, "**Total**")[pass_rate], "50.0%", "in:\n{}", report);
+}
@@ -0,0 +1,36 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::process::Command;
+
+use common::*;
+
+/// Runs junit2md on the fixtures with stderr attached to a pseudo-terminal via `script`
+/// and returns what was drawn there. Report itself is discarded.
+fn terminal_stderr(fixtures: &[&str]) -> String {
+    let mut command_line = format!("'{}' --no-config", env!("CARGO_BIN_EXE_junit2md"));
+    for fixture in fixtures {
+        command_line.push_str(&format!(" '{}'", fixture_path(fixture)));
+    }
+    command_line.push_str(" > /dev/null");
+
+    let output = Command::new("script")
+        .args(["-qec", &command_line, "/dev/null"])
+        .output()
+        .expect("failed to run script");
+    assert!(output.status.success(), "junit2md failed: {}", String::from_utf8_lossy(&output.stdout));
+    return String::from_utf8_lossy(&output.stdout).into_owned();
+}
+
+#[test]
+fn single_file_has_no_progress_bar() {
+    let stderr = terminal_stderr(&["junit-report-sample.xml"]);
+    assert!(!stderr.contains('█') && !stderr.contains('░'), "progress bar drawn: {:?}", stderr);
+}
+
+#[test]
+fn multiple_files_have_progress_bar() {
+    let stderr = terminal_stderr(&["junit-report-sample.xml", "junit-report-sample.xml"]);
+    assert!(stderr.contains('█'), "no progress bar in: {:?}", stderr);
+}
@@ -0,0 +1,19 @@
+mod common;
+
+use common::*;
+
+/// Fixture with properties given both as `value` attribute and as nested `<value>` element.
+const FIXTURE: &str = "junit-report-property-values.xml";
+
+#[test]
+fn attribute_and_element_values_are_listed() {
+    let report = generate_report(&[FIXTURE], &["-v"]);
+    assert!(report.contains("Properties:\n* java.version: 17.0.2\n* os.name: Linux\n"), "in:\n{}", report);
+}
+
+#[test]
+fn multi_line_values_go_to_code_blocks() {
+    let report = generate_report(&[FIXTURE], &["-v"]);
+    assert!(report.contains("**launcher.args:**\n\n```\n--select-package com.example\n--include-tag fast\n```\n"), "in:\n{}", report);
+    assert!(!report.contains("* launcher.args"), "in:\n{}", report);
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::*;
+
+/// Fixture with retried tests.
+const FIXTURE: &str = "junit-report-retries.xml";
+
+#[test]
+fn retries_column_flags_retried_tests() {
+    let report = generate_report(&[FIXTURE], &["--show-retries"]);
+    assert_eq!(table_row(&report, "Testcase name").last().unwrap(), "Retries", "in:\n{}", report);
+    assert_eq!(table_row(&report, "testStable").last().unwrap(), "0", "in:\n{}", report);
+    assert_eq!(table_row(&report, "testFlaky").last().unwrap(), "🔁 2", "in:\n{}", report);
+    assert_eq!(table_row(&report, "testBroken").last().unwrap(), "🔁 2", "in:\n{}", report);
+    assert_eq!(table_row(&report, "testPlain").last().unwrap(), "", "in:\n{}", report);
+}
+
+#[test]
+fn retries_column_is_hidden_by_default() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert!(!report.contains("Retries"), "in:\n{}", report);
+}
+
+#[test]
+fn malformed_retry_counts_are_ignored() {
+    let report_file = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("junit-report-malformed-retries.xml");
+    fs::write(&report_file, concat!(
+        "<testsuite name=\"com.example.RetriedTest\" tests=\"3\">\n",
+        "  <testcase name=\"testEmpty\" retries=\"\"/>\n",
+        "  <testcase name=\"testText\" attempts=\"twice\"/>\n",
+        "  <testcase name=\"testFallback\" retries=\"none\" attempts=\"2\"/>\n",
+        "</testsuite>\n",
+    )).unwrap();
+
+    let report = generate_report(&[report_file.to_str().unwrap()], &["--show-retries"]);
+    assert_eq!(table_row(&report, "testEmpty").last().unwrap(), "", "in:\n{}", report);
+    assert_eq!(table_row(&report, "testText").last().unwrap(), "", "in:\n{}", report);
+    assert_eq!(table_row(&report, "testFallback").last().unwrap(), "🔁 1", "in:\n{}", report);
+}
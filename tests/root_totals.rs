@@ -0,0 +1,19 @@
+mod common;
+
+use common::*;
+
+/// Fixture whose root `<testsuites>` declares more tests and time than its suites sum up to.
+const FIXTURE: &str = "junit-report-testsuites-root-attrs.xml";
+
+#[test]
+fn root_counts_are_used_in_total_row() {
+    let report = generate_report(&[FIXTURE], &["--no-details", "--bar-width", "0"]);
+    assert_eq!(table_row(&report, "**Total**")[1..7], ["3.250", "3", "1", "0", "2", "6"], "in:\n{}", report);
+    assert_eq!(table_row(&report, "ParserTest")[1..7], ["1.200", "1", "0", "0", "1", "2"], "in:\n{}", report);
+}
+
+#[test]
+fn root_counts_are_used_in_title() {
+    let report = generate_report(&[FIXTURE], &["--format", "text"]);
+    assert!(report.starts_with("Aggregated test report (6 tests, 2 failed)"), "in:\n{}", report);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::*;
+
+/// Generates plain text report of the fixture with tests selected by the given arguments.
+fn selected_tests_report(fixture: &str, args: &[&str]) -> String {
+    return generate_report(&[fixture], &[&TEXT_COUNTS[..], args].concat());
+}
+
+#[test]
+fn selected_tests_are_counted_across_suites() {
+    let report = selected_tests_report("junit-report-two-failing-suites.xml", &["--select-tests", "reconnects"]);
+    assert!(report.starts_with("Aggregated test report (2 tests, 2 failed)"), "in:\n{}", report);
+    assert_eq!(text_totals_rows(&report), vec![
+        vec!["ConnectionTest", "0.300", "0", "0", "0", "1", "1"],
+        vec!["ConnectionTest", "0.300", "0", "0", "0", "1", "1"],
+        vec!["Total", "N/A", "0", "0", "0", "2", "2"],
+    ], "in:\n{}", report);
+}
+
+#[test]
+fn suites_without_selected_tests_are_dropped() {
+    let report = selected_tests_report("junit-report-two-failing-suites.xml", &["--select-tests", r"server\.ConnectionTest\.connects$"]);
+    assert_eq!(text_totals_rows(&report), vec![
+        vec!["ConnectionTest", "0.210", "1", "0", "0", "0", "1"],
+        vec!["Total", "N/A", "1", "0", "0", "0", "1"],
+    ], "in:\n{}", report);
+}
+
+#[test]
+fn short_names_are_matched_without_class() {
+    let args = ["--select-tests", r"^com\.example\.client", "--select-tests-by", "short"];
+    let report = selected_tests_report("junit-report-two-failing-suites.xml", &args);
+    assert!(report.contains("No test suites found"), "in:\n{}", report);
+
+    let report = selected_tests_report("junit-report-two-failing-suites.xml", &["--select-tests", "^connects$", "--select-tests-by", "short"]);
+    assert!(report.starts_with("Aggregated test report (2 tests, 0 failed)"), "in:\n{}", report);
+}
+
+#[test]
+fn single_suite_is_recounted() {
+    let report = selected_tests_report("junit-report-suite-with-classes.xml", &["--select-tests", "Integration"]);
+    assert!(report.starts_with("ActivityOpenedFromPocketCodeNewImageTest (2 tests)"), "in:\n{}", report);
+    assert!(report.contains("Skipped          1"), "in:\n{}", report);
+    assert!(!report.contains("LandscapeTest"), "in:\n{}", report);
+}
+
+#[test]
+fn invalid_expression_is_an_error() {
+    let output = run(&["junit-report-two-failing-suites.xml"], &["--select-tests", "("]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--select-tests"));
+}
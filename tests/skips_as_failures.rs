@@ -0,0 +1,31 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn skipped_tests_pick_failed_exit_code() {
+    let output = run(&["junit-report-status-skips.xml"], &["--exit-policy", "failed=2,skipped=3"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = run(&["junit-report-status-skips.xml"], &["--exit-policy", "failed=2,skipped=3", "--skips-as-failures"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn skipped_tests_turn_health_badge_red() {
+    let report = generate_report(&["junit-report-status-skips.xml"], &["--health-badge"]);
+    assert!(report.contains("🟡 Some tests were skipped"), "in:\n{}", report);
+
+    let report = generate_report(&["junit-report-status-skips.xml"], &["--health-badge", "--skips-as-failures"]);
+    assert!(report.contains("🔴 Some tests were skipped"), "in:\n{}", report);
+}
+
+#[test]
+fn skipped_tests_are_not_excluded_from_pass_rate() {
+    let fixtures = ["junit-report-status-skips.xml", "junit-report-sample.xml"];
+    let args = ["--format", "text", "--bar-width", "0", "--exclude-skipped-from-rate", "--skips-as-failures"];
+    let report = generate_report(&fixtures, &args);
+    let row = report.lines().find(|line| line.starts_with("IndexTest")).expect("no suite row");
+    assert!(row.trim_end().ends_with("50.0%"), "unexpected pass rate in: {}", row);
+    assert!(row.contains(" 2 "), "skipped tests should still be shown in: {}", row);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::*;
+
+/// Fixture with failures of several classes.
+const FIXTURE: &str = "junit-report-unsorted-failures.xml";
+
+/// Returns test names of the failure details in plain text report, in order.
+fn failure_names(report: &str) -> Vec<String> {
+    return report.lines()
+        .skip_while(|line| *line != "Failures:")
+        .filter(|line| line.starts_with('['))
+        .map(|line| line.split_once("] ").unwrap().1.to_owned())
+        .collect();
+}
+
+/// Returns pairs of test name and failure reference of the Markdown breakdown table.
+fn breakdown_references(report: &str) -> Vec<(String, String)> {
+    return report.lines()
+        .filter(|line| line.contains("](#c-"))
+        .map(|line| {
+            let cells: Vec<&str> = line.split('|').map(str::trim).collect();
+            let anchor = cells[4].split("(#").nth(1).unwrap().trim_end_matches(')');
+            (cells[1].to_owned(), anchor.to_owned())
+        })
+        .collect();
+}
+
+#[test]
+fn failures_are_in_input_order_by_default() {
+    let report = generate_report(&[FIXTURE], &["--format", "text"]);
+    assert_eq!(failure_names(&report), vec!["rejectsEmptyCart", "removesItems", "appliesCoupon", "clearsCart"], "in:\n{}", report);
+}
+
+#[test]
+fn failures_are_sorted_by_class_then_name() {
+    let report = generate_report(&[FIXTURE], &["--format", "text", "--sort-failures", "class"]);
+    assert_eq!(failure_names(&report), vec!["clearsCart", "removesItems", "appliesCoupon", "rejectsEmptyCart"], "in:\n{}", report);
+}
+
+#[test]
+fn failures_are_sorted_by_name() {
+    let report = generate_report(&[FIXTURE], &["--format", "text", "--sort-failures", "name"]);
+    assert_eq!(failure_names(&report), vec!["appliesCoupon", "clearsCart", "rejectsEmptyCart", "removesItems"], "in:\n{}", report);
+}
+
+#[test]
+fn slowest_failures_go_first() {
+    let report = generate_report(&[FIXTURE], &["--format", "text", "--sort-failures", "time"]);
+    assert_eq!(failure_names(&report), vec!["removesItems", "appliesCoupon", "clearsCart", "rejectsEmptyCart"], "in:\n{}", report);
+}
+
+#[test]
+fn breakdown_links_follow_sorted_failures() {
+    let report = generate_report(&[FIXTURE], &["--format", "markdown", "--sort-failures", "class"]);
+    let references = breakdown_references(&report);
+    assert_eq!(references.len(), 4, "in:\n{}", report);
+    for (name, anchor) in references {
+        let target = format!("<a id=\"{}\"/>", anchor);
+        let details = &report[report.find(&target).unwrap_or_else(|| panic!("no {} in:\n{}", target, report))..];
+        let heading = details.lines().find(|line| line.starts_with("### ")).unwrap();
+        assert_eq!(heading, format!("### {} ###", name), "in:\n{}", report);
+    }
+}
+
+#[test]
+fn first_sorted_failures_are_shown() {
+    let report = generate_report(&[FIXTURE], &["--format", "text", "--sort-failures", "class", "--max-failures", "2"]);
+    assert_eq!(failure_names(&report), vec!["clearsCart", "removesItems"], "in:\n{}", report);
+}
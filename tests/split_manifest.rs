@@ -0,0 +1,34 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::*;
+
+#[test]
+fn manifest_lists_written_suite_reports() {
+    let output_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("split-manifest");
+    let _ = fs::remove_dir_all(&output_dir);
+    let manifest_file = output_dir.join("manifest.json");
+
+    let args = ["--split-output", output_dir.to_str().unwrap(), "--split-manifest", manifest_file.to_str().unwrap()];
+    generate_report(&["junit-report-two-failing-suites.xml"], &args);
+
+    let manifest = fs::read_to_string(&manifest_file).expect("manifest was not written");
+    let index_file = output_dir.join("index.md");
+    assert!(manifest.contains(&format!("\"index\": \"{}\"", index_file.display())), "in:\n{}", manifest);
+
+    for suite in ["com.example.client.ConnectionTest", "com.example.server.ConnectionTest"] {
+        let suite_file = output_dir.join(format!("{}.md", suite));
+        assert!(suite_file.exists(), "{} was not written", suite_file.display());
+
+        let entry = format!("\"file\": \"{}\",\n      \"name\": \"{}\",\n      \"tests\": 2,\n      \"success\": 1,", suite_file.display(), suite);
+        assert!(manifest.contains(&entry), "no entry for {} in:\n{}", suite, manifest);
+    }
+}
+
+#[test]
+fn manifest_requires_split_output() {
+    let output = run(&["junit-report-two-failing-suites.xml"], &["--split-manifest", "manifest.json"]);
+    assert!(!output.status.success());
+}
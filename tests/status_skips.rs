@@ -0,0 +1,22 @@
+mod common;
+
+use common::*;
+
+/// Fixture with tests skipped only by status attribute.
+const FIXTURE: &str = "junit-report-status-skips.xml";
+
+#[test]
+fn tests_with_skipped_status_are_skipped() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert_eq!(table_row(&report, "compactsOnLowMemory")[1], "✂", "in:\n{}", report);
+    assert_eq!(table_row(&report, "rebuildsCorruptIndex")[1], "✂", "in:\n{}", report);
+    assert_eq!(table_row(&report, "buildsIndex")[1], "✓", "in:\n{}", report);
+}
+
+#[test]
+fn status_skips_match_declared_totals() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert_eq!(table_row(&report, "Skipped")[1], "2", "in:\n{}", report);
+    assert_eq!(table_row(&report, "**Success**")[1], "2", "in:\n{}", report);
+    assert!(!report.contains("[^totals]"), "in:\n{}", report);
+}
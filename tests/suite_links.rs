@@ -0,0 +1,48 @@
+mod common;
+
+use common::*;
+
+
+/// Returns targets of links to suites in the totals table.
+fn suite_links(report: &str) -> Vec<String> {
+    return report.split("](#").skip(1)
+        .filter_map(|rest| rest.split(')').next())
+        .filter(|target| target.starts_with("suite-"))
+        .map(str::to_owned)
+        .collect();
+}
+
+#[test]
+fn every_suite_link_has_anchor() {
+    let report = generate_report(&["junit-report-two-failing-suites.xml"], &[]);
+    let links = suite_links(&report);
+    assert_eq!(links, vec!["suite-0", "suite-1"], "in:\n{}", report);
+    for link in links {
+        assert_eq!(report.matches(&format!("<a id=\"{}\"/>", link)).count(), 1, "no anchor for {} in:\n{}", link, report);
+    }
+}
+
+#[test]
+fn suite_anchor_precedes_its_first_failure() {
+    let report = generate_report(&["junit-report-two-failing-suites.xml"], &[]);
+    let anchor = report.find("<a id=\"suite-1\"/>").unwrap();
+    let suite = report.find("* Suite: com.example.server.ConnectionTest").unwrap();
+    assert!(anchor < suite, "in:\n{}", report);
+    assert!(!report[..anchor].contains("com.example.server.ConnectionTest"), "in:\n{}", report);
+}
+
+#[test]
+fn suites_with_hidden_failures_are_not_linked() {
+    let report = generate_report(&["junit-report-two-failing-suites.xml"], &["--max-failures", "1"]);
+    assert_eq!(suite_links(&report), vec!["suite-0"], "in:\n{}", report);
+    assert!(!report.contains("<a id=\"suite-1\"/>"), "in:\n{}", report);
+}
+
+#[test]
+fn no_links_without_failure_details() {
+    let report = generate_report(&["junit-report-two-failing-suites.xml"], &["--no-details"]);
+    assert!(suite_links(&report).is_empty(), "in:\n{}", report);
+
+    let report = generate_report(&["junit-report-common-properties.xml"], &[]);
+    assert!(suite_links(&report).is_empty(), "in:\n{}", report);
+}
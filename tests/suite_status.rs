@@ -0,0 +1,37 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use common::*;
+
+#[test]
+fn suite_status_lists_results_with_tests() {
+    let report = generate_report(&["junit-report-retries.xml"], &["--suite-status"]);
+    assert!(report.contains("\n✓ 3 passed · ✗ 1 failed\n"), "in:\n{}", report);
+}
+
+#[test]
+fn suite_status_uses_custom_symbols() {
+    let report = generate_report(&["junit-report-retries.xml"], &["--suite-status", "--symbol-pass", "OK", "--symbol-fail", "FAIL"]);
+    assert!(report.contains("\nOK 3 passed · FAIL 1 failed\n"), "in:\n{}", report);
+}
+
+#[test]
+fn empty_symbol_from_config_file_is_rejected() {
+    let config_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("empty-symbol-config");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(config_dir.join(".junit2md.toml"), "[symbols]\nfail = \" \"\n").unwrap();
+
+    // config file is only read without --no-config, so the common helpers can't be used
+    let output = Command::new(env!("CARGO_BIN_EXE_junit2md"))
+        .current_dir(&config_dir)
+        .env("HOME", &config_dir)
+        .arg(fixture_path("junit-report-retries.xml"))
+        .output()
+        .expect("failed to run junit2md");
+    assert!(!output.status.success(), "empty symbol was accepted");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("fail symbol must not be empty"), "unexpected error: {}", stderr);
+}
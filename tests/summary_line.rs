@@ -0,0 +1,16 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn summary_line_follows_the_title() {
+    let report = generate_report(&["junit-report-two-failing-suites.xml"], &[]);
+    let line = report.lines().skip_while(|line| !line.starts_with("====")).find(|line| !line.starts_with("====") && !line.is_empty());
+    assert_eq!(line, Some("Processed 2 suites containing 4 tests: 2 passed, 2 failed, 0 skipped."), "in:\n{}", report);
+}
+
+#[test]
+fn summary_line_can_be_suppressed() {
+    let report = generate_report(&["junit-report-two-failing-suites.xml"], &["--no-summary-line"]);
+    assert!(!report.contains("Processed"), "in:\n{}", report);
+}
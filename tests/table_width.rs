@@ -0,0 +1,49 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::*;
+
+/// Fixture with skipped tests.
+const FIXTURE: &str = "junit-report-status-skips.xml";
+
+#[test]
+fn columns_have_fixed_width() {
+    let report = generate_report(&[FIXTURE], &["--width", "12"]);
+    assert!(report.contains("|Testcase na…|   Status   |    Time    |   Cause    |\n"), "in:\n{}", report);
+    assert!(report.contains("|buildsIndex |     ✓      |    0.25    |            |\n"), "in:\n{}", report);
+    assert!(report.contains("|compactsOnL…|"), "in:\n{}", report);
+}
+
+#[test]
+fn bold_cells_are_not_shortened() {
+    let report = generate_report(&[FIXTURE], &["--width", "8"]);
+    assert!(report.contains("|**Success**|   2    |"), "in:\n{}", report);
+}
+
+#[test]
+fn width_must_fit_ellipsis() {
+    let output = run(&[FIXTURE], &["--width", "2"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn escaped_brackets_are_not_a_link() {
+    let report_file = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("junit-report-bracket-names.xml");
+    fs::write(&report_file, concat!(
+        "<testsuite name=\"BracketTest\" tests=\"1\">\n",
+        "  <testcase name=\"[parameterized](case)\"/>\n",
+        "</testsuite>\n",
+    )).unwrap();
+
+    let report = generate_report(&[report_file.to_str().unwrap()], &["--width", "12"]);
+    assert!(report.contains(r"|\[parameteri…|"), "in:\n{}", report);
+}
+
+#[test]
+fn link_text_is_shortened_inside_link() {
+    let report = generate_report(&["junit-report-two-failing-suites.xml"], &["--width", "8"]);
+    assert!(report.contains("|[Connect…](#suite-0)|"), "in:\n{}", report);
+    assert!(report.contains("|[Connect…](#suite-1)|"), "in:\n{}", report);
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn optional_sections_are_in_text_report() {
+    let report = generate_report(&["junit-report-skip-body.xml"], &["--format", "text", "--health-badge", "--list-skips", "--footer"]);
+    assert!(report.contains("\n\n🟡 Some tests were skipped\n\n"), "no health badge in:\n{}", report);
+    assert!(report.contains("Skipped tests:\n\nTestcase name  "), "no skipped list in:\n{}", report);
+    assert!(report.trim_end().lines().last().unwrap().starts_with("Generated by junit2md "), "no footer in:\n{}", report);
+}
+
+#[test]
+fn table_of_contents_is_in_text_report() {
+    let report = generate_report(&["junit-report-two-failing-suites.xml"], &["--format", "text", "--toc"]);
+    let toc: Vec<&str> = report.lines().filter(|line| line.starts_with("* ")).collect();
+    assert_eq!(toc.len(), 2, "unexpected table of contents in:\n{}", report);
+    assert!(toc.iter().all(|line| line.contains("(1 failure, see [")), "unexpected table of contents in:\n{}", report);
+}
+
+#[test]
+fn properties_are_in_verbose_text_report() {
+    let report = generate_report(&["junit-report-property-values.xml"], &["--format", "text", "-v"]);
+    assert!(report.contains("Properties:\n    "), "no properties in:\n{}", report);
+}
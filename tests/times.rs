@@ -0,0 +1,41 @@
+mod common;
+
+use common::*;
+
+/// Fixture with negative, locale-formatted and malformed times.
+const FIXTURE: &str = "junit-report-malformed-times.xml";
+
+/// Returns the Time cell of the test in the breakdown table.
+fn test_time(report: &str, test_name: &str) -> Option<String> {
+    let row = report.lines().find(|line| line.starts_with(test_name))?;
+    return row.split_whitespace().nth(2).map(str::to_owned);
+}
+
+#[test]
+fn negative_times_are_shown_as_zero() {
+    let output = junit2md()
+        .args(["--format", "text"])
+        .arg(fixture_path(FIXTURE))
+        .output()
+        .expect("failed to run junit2md");
+    assert!(output.status.success(), "junit2md failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(test_time(&report, "negativeZero").as_deref(), Some("0"), "in:\n{}", report);
+    assert_eq!(test_time(&report, "negative ").as_deref(), Some("0"), "in:\n{}", report);
+
+    let warnings = String::from_utf8(output.stderr).unwrap();
+    assert!(warnings.contains("test case negativeZero has negative time -0.0"), "in:\n{}", warnings);
+}
+
+#[test]
+fn thousands_separators_are_dropped() {
+    let report = generate_report(&[FIXTURE], &["--format", "text"]);
+    assert_eq!(test_time(&report, "localeComma").as_deref(), Some("1234.5"), "in:\n{}", report);
+}
+
+#[test]
+fn uninterpretable_times_are_shown_as_is() {
+    let report = generate_report(&[FIXTURE], &["--format", "text"]);
+    assert_eq!(test_time(&report, "notANumber").as_deref(), Some("abc"), "in:\n{}", report);
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use std::fs;
+use std::process::Output;
+
+use common::*;
+
+/// Runs junit2md on a single suite fixture with the given extra arguments.
+fn run_with_title(args: &[&str]) -> Output {
+    return run(&["junit-report-mixed-languages.xml"], args);
+}
+
+/// Writes title file with the given content to the temporary directory, returns its path.
+fn write_title_file(name: &str, content: &str) -> String {
+    let path = format!("{}/{}", env!("CARGO_TARGET_TMPDIR"), name);
+    fs::write(&path, content).unwrap();
+    return path;
+}
+
+#[test]
+fn title_is_first_non_empty_line() {
+    let title_file = write_title_file("title-readme.md", "\n  \n# Billing module\n\nHandles invoices.\n");
+    let output = run_with_title(&["--report-title-from-file", &title_file]);
+    assert!(output.status.success(), "junit2md failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(report.lines().find(|line| !line.is_empty()), Some("Billing module (6 tests)"), "in:\n{}", report);
+}
+
+#[test]
+fn explicit_title_wins_over_title_file() {
+    let title_file = write_title_file("title-overridden.txt", "Billing module\n");
+    let output = run_with_title(&["--report-title-from-file", &title_file, "--title", "Nightly run", "--no-header-counts"]);
+    assert!(output.status.success(), "junit2md failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(report.lines().find(|line| !line.is_empty()), Some("Nightly run"), "in:\n{}", report);
+}
+
+#[test]
+fn title_file_is_used_in_text_format() {
+    let title_file = write_title_file("title-text.txt", "Billing module\n");
+    let output = run_with_title(&["--report-title-from-file", &title_file, "--format", "text", "--no-header-counts"]);
+    assert!(output.status.success(), "junit2md failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(report.lines().find(|line| !line.is_empty()), Some("Billing module"), "in:\n{}", report);
+}
+
+#[test]
+fn missing_title_file_is_an_error() {
+    let title_file = format!("{}/no-such-title.md", env!("CARGO_TARGET_TMPDIR"));
+    let output = run_with_title(&["--report-title-from-file", &title_file]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Can't read title file"));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn blank_title_file_is_an_error() {
+    let title_file = write_title_file("title-blank.md", "\n   \n#\n");
+    let output = run_with_title(&["--report-title-from-file", &title_file]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is empty"));
+}
@@ -0,0 +1,21 @@
+mod common;
+
+use common::*;
+
+/// Fixture with classes in Java packages.
+const FIXTURE: &str = "junit-report-retries.xml";
+
+#[test]
+fn shortened_names_have_full_name_tooltip() {
+    for format in ["markdown", "html"] {
+        let report = generate_report(&[FIXTURE], &["--show-classname", "--format", format]);
+        assert!(report.contains("<span title=\"com.example.RetriedTest\">RetriedTest</span>"), "no tooltip in:\n{}", report);
+    }
+}
+
+#[test]
+fn names_without_package_have_no_tooltip() {
+    let report = generate_report(&[FIXTURE], &["--show-classname", "--format", "markdown"]);
+    assert!(report.contains("|testBroken "), "in:\n{}", report);
+    assert!(!report.contains("<span title=\"testBroken\">"), "in:\n{}", report);
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use common::*;
+
+/// Fixture with a test that has both a failure and an error.
+const FIXTURE: &str = "junit-report-error-and-failure.xml";
+
+#[test]
+fn declared_counts_are_flagged_when_test_is_counted_twice() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert_eq!(table_row(&report, "Failed")[1], "2", "in:\n{}", report);
+    assert_eq!(table_row(&report, "**Success**")[1], "1 ⚠[^totals]", "in:\n{}", report);
+}
+
+#[test]
+fn test_with_error_and_failure_is_counted_once_from_testcases() {
+    let report = generate_report(&[FIXTURE], &["--trust-testcases"]);
+    assert_eq!(table_row(&report, "Failed")[1], "1", "in:\n{}", report);
+    assert_eq!(table_row(&report, "**Success**")[1], "2", "in:\n{}", report);
+    assert!(!report.contains("[^totals]"), "in:\n{}", report);
+}
+
+#[test]
+fn test_with_error_and_failure_is_reported_as_error() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert_eq!(table_row(&report, "testBoth")[1], "‼", "in:\n{}", report);
+    assert_eq!(report.matches("### testBoth ###").count(), 1, "in:\n{}", report);
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::*;
+
+/// Generates plain text report of the Bazel-like fixture, whose declared counts disagree with its test cases.
+fn bazel_report(args: &[&str]) -> String {
+    return generate_report(&["junit-report-bazel.xml"], &[&TEXT_COUNTS[..], args].concat());
+}
+
+#[test]
+fn declared_counts_are_trusted_by_default() {
+    let report = bazel_report(&[]);
+    assert!(report.starts_with("Aggregated test report (8 tests, 2 failed)"), "in:\n{}", report);
+    assert_eq!(text_totals_rows(&report)[1], vec!["//billing:tax_test", "1.500", "4", "0", "0", "0", "4"], "in:\n{}", report);
+}
+
+#[test]
+fn counts_are_recomputed_from_testcases() {
+    let report = bazel_report(&["--trust-testcases"]);
+    assert!(report.starts_with("Aggregated test report (5 tests, 2 failed)"), "in:\n{}", report);
+    assert_eq!(text_totals_rows(&report), vec![
+        vec!["//billing:invoice_test", "0.500", "1", "1", "0", "1", "3"],
+        vec!["//billing:tax_test", "1.500", "1", "0", "0", "1", "2"],
+        vec!["Total", "2.000", "2", "1", "0", "2", "5"],
+    ], "in:\n{}", report);
+}
+
+#[test]
+fn summary_line_uses_recomputed_counts() {
+    let report = bazel_report(&["--trust-testcases"]);
+    assert!(report.contains("Processed 2 suites containing 5 tests: 2 passed, 2 failed, 1 skipped."), "in:\n{}", report);
+}
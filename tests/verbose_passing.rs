@@ -0,0 +1,38 @@
+mod common;
+
+use common::*;
+
+/// Fixture with passing tests that print to stdout and stderr.
+const FIXTURE: &str = "junit-report-passing-output.xml";
+
+/// Returns the section with output of passed tests, empty if there's none.
+fn passing_output(report: &str) -> &str {
+    return report.find("Output of passed tests\n").map(|start| &report[start..]).unwrap_or_default();
+}
+
+#[test]
+fn output_of_passed_tests_is_hidden_by_default() {
+    let report = generate_report(&[FIXTURE], &[]);
+    assert_eq!(passing_output(&report), "", "in:\n{}", report);
+    assert!(!report.contains("clock advanced"), "in:\n{}", report);
+}
+
+#[test]
+fn output_of_passed_tests_is_shown() {
+    let report = generate_report(&[FIXTURE], &["--verbose-passing"]);
+    let output = passing_output(&report);
+    assert!(output.contains("### survivesConcurrentWrites ###\n\n<details>\n    <summary>Click to show test stdout</summary>\n\n    \
+                             attempt 1: 2 writers collided, retrying\n    attempt 2: ok\n</details>"), "in:\n{}", report);
+    assert!(output.contains("<summary>Click to show test stderr</summary>\n\n    WARN lock held for 850ms\n"), "in:\n{}", report);
+    assert!(output.contains("### expiresEntries ###"), "in:\n{}", report);
+    assert!(output.contains("    clock advanced by 60s\n"), "in:\n{}", report);
+    assert!(!output.contains("evictsOldestEntry"), "tests without output should not be listed in:\n{}", report);
+}
+
+#[test]
+fn output_of_passed_tests_respects_tail_output() {
+    let report = generate_report(&[FIXTURE], &["--verbose-passing", "--tail-output", "1"]);
+    let output = passing_output(&report);
+    assert!(output.contains("    ... (1 earlier line omitted)\n    attempt 2: ok\n"), "in:\n{}", report);
+    assert!(!output.contains("attempt 1"), "in:\n{}", report);
+}